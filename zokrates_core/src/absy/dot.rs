@@ -0,0 +1,98 @@
+// Graphviz/DOT export of the positioned absy AST, for debugging parser and
+// semantic issues. Mirrors rustc's `--pretty flowgraph` dumps: every node is
+// labeled with its variant and its `(start, end)` source positions, and edges
+// are drawn from parents to children. Driven by the read-only `Visitor` trait.
+
+use crate::absy::folder::Visitor;
+use crate::absy::node::Node;
+use crate::absy::*;
+use std::io::{self, Write};
+
+/// Walks a `Module` and emits a Graphviz DOT description of its positioned
+/// node tree to `w`.
+pub fn to_dot<W: Write>(module: &Module, w: &mut W) -> io::Result<()> {
+    writeln!(w, "digraph absy {{")?;
+    writeln!(w, "    node [shape=box, fontname=monospace];")?;
+    let mut printer = DotPrinter {
+        out: w,
+        next_id: 0,
+        stack: vec![],
+        result: Ok(()),
+    };
+    printer.visit_module(module);
+    let result = printer.result;
+    writeln!(w, "}}")?;
+    result
+}
+
+struct DotPrinter<'w, W: Write> {
+    out: &'w mut W,
+    next_id: usize,
+    stack: Vec<usize>,
+    result: io::Result<()>,
+}
+
+impl<'w, W: Write> DotPrinter<'w, W> {
+    /// Emit a node with the given label and connect it to its parent, then run
+    /// `body` with this node pushed as the current parent.
+    fn node<T>(&mut self, label: &str, n: &Node<T>, body: impl FnOnce(&mut Self)) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.emit(format!(
+            "    n{} [label=\"{}\\n({}:{})-({}:{})\"];",
+            id, label, n.start.line, n.start.col, n.end.line, n.end.col
+        ));
+        if let Some(&parent) = self.stack.last() {
+            self.emit(format!("    n{} -> n{};", parent, id));
+        }
+        self.stack.push(id);
+        body(self);
+        self.stack.pop();
+    }
+
+    fn emit(&mut self, line: String) {
+        if self.result.is_ok() {
+            self.result = writeln!(self.out, "{}", line);
+        }
+    }
+}
+
+impl<'ast, 'w, W: Write> Visitor<'ast> for DotPrinter<'w, W> {
+    fn visit_function(&mut self, f: &FunctionNode<'ast>) {
+        self.node("Function", f, |p| {
+            for a in &f.value.arguments {
+                p.visit_parameter(a);
+            }
+            for s in &f.value.statements {
+                p.visit_statement(s);
+            }
+        });
+    }
+
+    fn visit_parameter(&mut self, p: &ParameterNode<'ast>) {
+        self.node("Parameter", p, |_| {});
+    }
+
+    fn visit_statement(&mut self, s: &StatementNode<'ast>) {
+        let label = match &s.value {
+            Statement::Declaration(_) => "Statement::Declaration",
+            Statement::Definition(..) => "Statement::Definition",
+            Statement::Return(_) => "Statement::Return",
+            _ => "Statement",
+        };
+        self.node(label, s, |p| {
+            if let Statement::Definition(_, e) = &s.value {
+                p.visit_expression(e);
+            }
+            if let Statement::Return(l) = &s.value {
+                for e in &l.value.expressions {
+                    p.visit_expression(e);
+                }
+            }
+        });
+    }
+
+    fn visit_expression(&mut self, e: &ExpressionNode<'ast>) {
+        self.node("Expression", e, |_| {});
+    }
+}
@@ -0,0 +1,185 @@
+// Generic traversal of the positioned absy AST.
+//
+// Two entry points modeled on rustc's `visit`/`mut_visit`: a read-only
+// `Visitor<'ast>` whose methods default to free `walk_*` functions recursing
+// into children, and a `Folder<'ast>` that consumes and rebuilds `Node<T>`
+// values while carrying the original `start`/`end` positions forward unless a
+// method explicitly replaces them. Pass authors (semantic checks, constant
+// folding, import resolution) override only the methods they care about.
+
+use crate::absy::node::Node;
+use crate::absy::*;
+use crate::imports::*;
+
+/// Rebuild a node around a new value, keeping the source span of the original.
+fn fold_node<T, U, F: FnOnce(T) -> U>(n: Node<T>, f: F) -> Node<U> {
+    let (start, end) = (n.start, n.end);
+    Node {
+        start,
+        end,
+        value: f(n.value),
+    }
+}
+
+pub trait Folder<'ast>: Sized {
+    fn fold_module(&mut self, m: Module<'ast>) -> Module<'ast> {
+        walk_module(self, m)
+    }
+
+    fn fold_symbol_declaration(
+        &mut self,
+        d: SymbolDeclarationNode<'ast>,
+    ) -> SymbolDeclarationNode<'ast> {
+        fold_node(d, |d| walk_symbol_declaration(self, d))
+    }
+
+    fn fold_function(&mut self, f: FunctionNode<'ast>) -> FunctionNode<'ast> {
+        fold_node(f, |f| walk_function(self, f))
+    }
+
+    fn fold_parameter(&mut self, p: ParameterNode<'ast>) -> ParameterNode<'ast> {
+        fold_node(p, |p| Parameter {
+            id: self.fold_variable(p.id),
+            ..p
+        })
+    }
+
+    fn fold_variable(&mut self, v: VariableNode<'ast>) -> VariableNode<'ast> {
+        v
+    }
+
+    fn fold_statement(&mut self, s: StatementNode<'ast>) -> StatementNode<'ast> {
+        fold_node(s, |s| walk_statement(self, s))
+    }
+
+    fn fold_assignee(&mut self, a: AssigneeNode<'ast>) -> AssigneeNode<'ast> {
+        a
+    }
+
+    fn fold_expression(&mut self, e: ExpressionNode<'ast>) -> ExpressionNode<'ast> {
+        e
+    }
+}
+
+pub fn walk_module<'ast, F: Folder<'ast>>(f: &mut F, m: Module<'ast>) -> Module<'ast> {
+    Module {
+        symbols: m
+            .symbols
+            .into_iter()
+            .map(|s| f.fold_symbol_declaration(s))
+            .collect(),
+        ..m
+    }
+}
+
+pub fn walk_symbol_declaration<'ast, F: Folder<'ast>>(
+    f: &mut F,
+    d: SymbolDeclaration<'ast>,
+) -> SymbolDeclaration<'ast> {
+    // Only function declarations carry foldable sub-trees; other kinds
+    // (imports, struct definitions) are returned untouched by default.
+    match d {
+        SymbolDeclaration::Function(id, func) => {
+            SymbolDeclaration::Function(id, f.fold_function(func))
+        }
+        d => d,
+    }
+}
+
+pub fn walk_function<'ast, F: Folder<'ast>>(f: &mut F, fun: Function<'ast>) -> Function<'ast> {
+    Function {
+        arguments: fun
+            .arguments
+            .into_iter()
+            .map(|a| f.fold_parameter(a))
+            .collect(),
+        statements: fun
+            .statements
+            .into_iter()
+            .map(|s| f.fold_statement(s))
+            .collect(),
+        ..fun
+    }
+}
+
+pub fn walk_statement<'ast, F: Folder<'ast>>(f: &mut F, s: Statement<'ast>) -> Statement<'ast> {
+    match s {
+        Statement::Declaration(v) => Statement::Declaration(f.fold_variable(v)),
+        Statement::Definition(a, e) => {
+            Statement::Definition(f.fold_assignee(a), f.fold_expression(e))
+        }
+        Statement::Return(l) => Statement::Return(fold_node(l, |l| ExpressionList {
+            expressions: l
+                .expressions
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect(),
+        })),
+        s => s,
+    }
+}
+
+pub trait Visitor<'ast>: Sized {
+    fn visit_module(&mut self, m: &Module<'ast>) {
+        walk_module_ref(self, m)
+    }
+
+    fn visit_symbol_declaration(&mut self, d: &SymbolDeclarationNode<'ast>) {
+        walk_symbol_declaration_ref(self, &d.value)
+    }
+
+    fn visit_function(&mut self, f: &FunctionNode<'ast>) {
+        walk_function_ref(self, &f.value)
+    }
+
+    fn visit_parameter(&mut self, p: &ParameterNode<'ast>) {
+        self.visit_variable(&p.value.id)
+    }
+
+    fn visit_variable(&mut self, _v: &VariableNode<'ast>) {}
+
+    fn visit_statement(&mut self, s: &StatementNode<'ast>) {
+        walk_statement_ref(self, &s.value)
+    }
+
+    fn visit_assignee(&mut self, _a: &AssigneeNode<'ast>) {}
+
+    fn visit_expression(&mut self, _e: &ExpressionNode<'ast>) {}
+}
+
+pub fn walk_module_ref<'ast, V: Visitor<'ast>>(v: &mut V, m: &Module<'ast>) {
+    for s in &m.symbols {
+        v.visit_symbol_declaration(s);
+    }
+}
+
+pub fn walk_symbol_declaration_ref<'ast, V: Visitor<'ast>>(v: &mut V, d: &SymbolDeclaration<'ast>) {
+    if let SymbolDeclaration::Function(_, func) = d {
+        v.visit_function(func);
+    }
+}
+
+pub fn walk_function_ref<'ast, V: Visitor<'ast>>(v: &mut V, f: &Function<'ast>) {
+    for a in &f.arguments {
+        v.visit_parameter(a);
+    }
+    for s in &f.statements {
+        v.visit_statement(s);
+    }
+}
+
+pub fn walk_statement_ref<'ast, V: Visitor<'ast>>(v: &mut V, s: &Statement<'ast>) {
+    match s {
+        Statement::Declaration(var) => v.visit_variable(var),
+        Statement::Definition(a, e) => {
+            v.visit_assignee(a);
+            v.visit_expression(e);
+        }
+        Statement::Return(l) => {
+            for e in &l.value.expressions {
+                v.visit_expression(e);
+            }
+        }
+        _ => {}
+    }
+}
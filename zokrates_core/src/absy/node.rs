@@ -1,5 +1,13 @@
+// `Position` is defined in `crate::parser` (outside this module). Extending it
+// with a byte `offset` and an optional `file` handle for multi-file source
+// tracking belongs in that definition; the spans threaded through `Node<T>`
+// below carry whatever `Position` exposes, so no change is needed here beyond
+// keeping positions orderable (see the `Ord`/`merge` impls).
 use crate::parser::Position;
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
 use zokrates_pest_ast::Span;
 
 #[derive(Clone)]
@@ -15,6 +23,17 @@ impl<T: fmt::Display> Node<T> {
     }
 }
 
+impl<T> Node<T> {
+    /// Compute the span covering both `self` and `other`, taking the earliest
+    /// start and the latest end position of the two nodes.
+    pub fn merge<U>(&self, other: &Node<U>) -> (Position, Position) {
+        (
+            std::cmp::min(self.start, other.start),
+            std::cmp::max(self.end, other.end),
+        )
+    }
+}
+
 impl<T: fmt::Display> fmt::Display for Node<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.value)
@@ -43,6 +62,13 @@ pub trait NodeValue: fmt::Display + fmt::Debug + Sized + PartialEq {
         Node::new(start, end, self)
     }
 
+    /// Build a node for a composite value whose span covers the range from the
+    /// start of `start_node` to the end of `end_node`, e.g. a binary expression
+    /// spanning both of its operands.
+    fn spanning<S, E>(self, start_node: &Node<S>, end_node: &Node<E>) -> Node<Self> {
+        Node::new(start_node.start, end_node.end, self)
+    }
+
     #[cfg(test)]
     fn mock(self) -> Node<Self> {
         Node::new(Position::mock(), Position::mock(), self)
@@ -65,6 +91,50 @@ pub trait NodeValue: fmt::Display + fmt::Debug + Sized + PartialEq {
     }
 }
 
+/// Runtime tag of an absy node's kind, so heterogeneous `Box<dyn AnyNode>`
+/// collections can be filtered without knowing the concrete value type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Expression,
+    ExpressionList,
+    Assignee,
+    Statement,
+    SymbolDeclaration,
+    UnresolvedType,
+    StructDefinition,
+    StructDefinitionField,
+    Function,
+    Module,
+    SymbolImport,
+    Variable,
+    Parameter,
+    Import,
+    Spread,
+    Range,
+}
+
+/// Maps a node value type to its runtime `NodeKind`.
+pub trait NodeValueKind {
+    fn node_kind() -> NodeKind;
+}
+
+/// Object-safe view over any positioned node, exposing its kind and span
+/// without exposing its concrete generic parameter.
+pub trait AnyNode {
+    fn kind(&self) -> NodeKind;
+    fn span(&self) -> (Position, Position);
+}
+
+impl<T: NodeValueKind> AnyNode for Node<T> {
+    fn kind(&self) -> NodeKind {
+        T::node_kind()
+    }
+
+    fn span(&self) -> (Position, Position) {
+        (self.start, self.end)
+    }
+}
+
 impl<V: NodeValue> From<V> for Node<V> {
     fn from(v: V) -> Node<V> {
         let mock_position = Position { col: 42, line: 42 };
@@ -76,25 +146,78 @@ use crate::absy::types::UnresolvedType;
 use crate::absy::*;
 use crate::imports::*;
 
-impl<'ast> NodeValue for Expression<'ast> {}
-impl<'ast> NodeValue for ExpressionList<'ast> {}
-impl<'ast> NodeValue for Assignee<'ast> {}
-impl<'ast> NodeValue for Statement<'ast> {}
-impl<'ast> NodeValue for SymbolDeclaration<'ast> {}
-impl NodeValue for UnresolvedType {}
-impl<'ast> NodeValue for StructDefinition<'ast> {}
-impl<'ast> NodeValue for StructDefinitionField<'ast> {}
-impl<'ast> NodeValue for Function<'ast> {}
-impl<'ast> NodeValue for Module<'ast> {}
-impl<'ast> NodeValue for SymbolImport<'ast> {}
-impl<'ast> NodeValue for Variable<'ast> {}
-impl<'ast> NodeValue for Parameter<'ast> {}
-impl<'ast> NodeValue for Import<'ast> {}
-impl<'ast> NodeValue for Spread<'ast> {}
-impl<'ast> NodeValue for Range<'ast> {}
+macro_rules! node_value {
+    ($t:ty, $kind:ident) => {
+        impl NodeValue for $t {}
+        impl NodeValueKind for $t {
+            fn node_kind() -> NodeKind {
+                NodeKind::$kind
+            }
+        }
+    };
+    (<'ast> $t:ty, $kind:ident) => {
+        impl<'ast> NodeValue for $t {}
+        impl<'ast> NodeValueKind for $t {
+            fn node_kind() -> NodeKind {
+                NodeKind::$kind
+            }
+        }
+    };
+}
+
+node_value!(<'ast> Expression<'ast>, Expression);
+node_value!(<'ast> ExpressionList<'ast>, ExpressionList);
+node_value!(<'ast> Assignee<'ast>, Assignee);
+node_value!(<'ast> Statement<'ast>, Statement);
+node_value!(<'ast> SymbolDeclaration<'ast>, SymbolDeclaration);
+node_value!(UnresolvedType, UnresolvedType);
+node_value!(<'ast> StructDefinition<'ast>, StructDefinition);
+node_value!(<'ast> StructDefinitionField<'ast>, StructDefinitionField);
+node_value!(<'ast> Function<'ast>, Function);
+node_value!(<'ast> Module<'ast>, Module);
+node_value!(<'ast> SymbolImport<'ast>, SymbolImport);
+node_value!(<'ast> Variable<'ast>, Variable);
+node_value!(<'ast> Parameter<'ast>, Parameter);
+node_value!(<'ast> Import<'ast>, Import);
+node_value!(<'ast> Spread<'ast>, Spread);
+node_value!(<'ast> Range<'ast>, Range);
 
 impl<T: PartialEq> PartialEq for Node<T> {
     fn eq(&self, other: &Node<T>) -> bool {
         self.value.eq(&other.value)
     }
 }
+
+impl<T: Eq> Eq for Node<T> {}
+
+impl<T: PartialOrd> PartialOrd for Node<T> {
+    fn partial_cmp(&self, other: &Node<T>) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord> Ord for Node<T> {
+    fn cmp(&self, other: &Node<T>) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: Hash> Hash for Node<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> Deref for Node<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Node<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
@@ -0,0 +1,82 @@
+//! Sparse multilinear-extension view of a constraint matrix.
+//!
+//! A sumcheck-based prover treats each R1CS matrix as a function over the
+//! boolean hypercube `{0,1}^s` (with `s = log2(rows) + log2(cols)`) and works
+//! with its unique multilinear extension to the whole field. Because the
+//! matrices are overwhelmingly zero, we store only the non-zero entries and
+//! evaluate the extension as a sum over them, each weighted by the multilinear
+//! equality polynomial `eq`.
+
+use zokrates_field::Field;
+
+/// A single non-zero matrix entry, addressed by its flattened row-major index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry<T> {
+    pub index: usize,
+    pub value: T,
+}
+
+/// Sparse multilinear extension of a matrix over `num_vars` boolean variables.
+pub struct SparseMle<T> {
+    pub num_vars: usize,
+    pub entries: Vec<Entry<T>>,
+}
+
+/// The multilinear equality polynomial `eq(index, point)`, i.e. the product
+/// over bits `i` of `point[i]` when bit `i` of `index` is 1 and `1 - point[i]`
+/// when it is 0. Equals 1 when `point` is the boolean vector `index`.
+fn eq<T: Field>(index: usize, point: &[T]) -> T {
+    let mut acc = T::one();
+    for (i, r) in point.iter().enumerate() {
+        let bit = (index >> i) & 1;
+        let factor = if bit == 1 {
+            r.clone()
+        } else {
+            T::one() - r.clone()
+        };
+        acc = acc * factor;
+    }
+    acc
+}
+
+impl<T: Field> SparseMle<T> {
+    /// Build a sparse MLE from flattened `(index, value)` pairs over a hypercube
+    /// of `num_vars` variables (so indices range over `0..2^num_vars`).
+    pub fn new(num_vars: usize, entries: Vec<Entry<T>>) -> Self {
+        SparseMle { num_vars, entries }
+    }
+
+    /// Build from a matrix given as dense rows. Zero entries are dropped and the
+    /// hypercube is sized to the next powers of two of the dimensions.
+    pub fn from_rows(rows: &[Vec<T>]) -> Self {
+        let n_rows = rows.len().next_power_of_two().max(1);
+        let n_cols = rows.iter().map(|r| r.len()).max().unwrap_or(0).next_power_of_two().max(1);
+        let row_bits = n_rows.trailing_zeros() as usize;
+        let col_bits = n_cols.trailing_zeros() as usize;
+
+        let mut entries = vec![];
+        for (i, row) in rows.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                if *value != T::zero() {
+                    entries.push(Entry {
+                        index: (i << col_bits) | j,
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+
+        SparseMle {
+            num_vars: row_bits + col_bits,
+            entries,
+        }
+    }
+
+    /// Evaluate the multilinear extension at `point` (length `num_vars`).
+    pub fn evaluate(&self, point: &[T]) -> T {
+        assert_eq!(point.len(), self.num_vars);
+        self.entries.iter().fold(T::zero(), |acc, e| {
+            acc + e.value.clone() * eq(e.index, point)
+        })
+    }
+}
@@ -0,0 +1,128 @@
+//! Linear-constraint elimination.
+//!
+//! Many constraints produced by flattening are purely linear: their quadratic
+//! term degenerates to a scalar multiple of a single linear combination (see
+//! `QuadComb::try_linear`). Such a constraint `l == r` defines one variable as
+//! an affine function of the others, so it can be removed and the variable
+//! substituted everywhere else. This shrinks the system before it is handed to
+//! a back-end.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::expression::{LinComb, QuadComb};
+use std::collections::BTreeMap;
+use zokrates_field::Field;
+
+/// A rank-1 constraint `quad == lin`.
+pub struct Constraint<T> {
+    pub quad: QuadComb<T>,
+    pub lin: LinComb<T>,
+}
+
+pub struct LinearEliminator<T> {
+    /// Variables that have been eliminated, mapped to the linear combination
+    /// they are equal to (never mentioning an already-eliminated variable).
+    substitutions: BTreeMap<FlatVariable, LinComb<T>>,
+}
+
+impl<T: Field> LinearEliminator<T> {
+    pub fn new() -> Self {
+        LinearEliminator {
+            substitutions: BTreeMap::new(),
+        }
+    }
+
+    /// Eliminate linear constraints, returning the reduced constraint list. The
+    /// recorded substitutions are available via `substitutions()` so a witness
+    /// for the eliminated variables can be recovered afterwards.
+    pub fn optimize(mut self, constraints: Vec<Constraint<T>>) -> Vec<Constraint<T>> {
+        let mut kept = vec![];
+
+        for c in constraints {
+            let quad = self.apply_quad(c.quad);
+            let lin = self.apply_lin(c.lin);
+
+            match quad.try_linear() {
+                // a linear constraint `left == lin`: try to isolate a variable
+                Some(left) => {
+                    let residual = (left - lin).into_canonical();
+                    match self.isolate(&residual) {
+                        Some((var, definition)) => {
+                            // keep earlier definitions free of the newly eliminated variable
+                            for def in self.substitutions.values_mut() {
+                                *def = def.clone().0.into_iter().fold(LinComb::zero(), |acc, (w, c)| {
+                                    if w == var {
+                                        acc + definition.clone() * &c
+                                    } else {
+                                        acc + LinComb::summand(c, w)
+                                    }
+                                });
+                            }
+                            self.substitutions.insert(var, definition);
+                        }
+                        // not reducible to a single fresh variable, keep it
+                        None => kept.push(Constraint {
+                            quad: residual.into(),
+                            lin: LinComb::zero(),
+                        }),
+                    }
+                }
+                None => kept.push(Constraint { quad, lin }),
+            }
+        }
+
+        // constraints kept early may mention variables eliminated after them
+        kept.into_iter()
+            .map(|c| Constraint {
+                quad: self.apply_quad(c.quad),
+                lin: self.apply_lin(c.lin),
+            })
+            .collect()
+    }
+
+    pub fn substitutions(&self) -> &BTreeMap<FlatVariable, LinComb<T>> {
+        &self.substitutions
+    }
+
+    /// Given `residual == 0`, pick a not-yet-eliminated variable with non-zero
+    /// coefficient and solve for it: `var = -(rest) / coeff`.
+    fn isolate(
+        &self,
+        residual: &crate::ir::expression::CanonicalLinComb<T>,
+    ) -> Option<(FlatVariable, LinComb<T>)> {
+        let pivot = residual
+            .0
+            .iter()
+            .find(|(var, _)| **var != FlatVariable::one() && !self.substitutions.contains_key(var))
+            .map(|(var, coeff)| (*var, coeff.clone()))?;
+
+        let (var, coeff) = pivot;
+        let rest = residual
+            .0
+            .iter()
+            .filter(|(v, _)| **v != var)
+            .fold(LinComb::zero(), |acc, (v, c)| {
+                acc + LinComb::summand(c.clone(), *v)
+            });
+
+        Some((var, (LinComb::zero() - rest) / &coeff))
+    }
+
+    fn apply_lin(&self, lin: LinComb<T>) -> LinComb<T> {
+        lin.0.into_iter().fold(LinComb::zero(), |acc, (var, coeff)| {
+            match self.substitutions.get(&var) {
+                Some(def) => acc + def.clone() * &coeff,
+                None => acc + LinComb::summand(coeff, var),
+            }
+        })
+    }
+
+    fn apply_quad(&self, quad: QuadComb<T>) -> QuadComb<T> {
+        QuadComb::from_linear_combinations(self.apply_lin(quad.left), self.apply_lin(quad.right))
+    }
+}
+
+impl<T: Field> Default for LinearEliminator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,133 @@
+//! Conversion of an R1CS constraint system into a Quadratic Arithmetic Program.
+//!
+//! Each of the three R1CS matrices `A`, `B`, `C` has one column per witness
+//! variable and one row per constraint. For a QAP we need, for every variable
+//! `j`, the polynomials `A_j(x)`, `B_j(x)`, `C_j(x)` that evaluate at the
+//! `i`-th constraint point to the `(i, j)` matrix entry. We pick the evaluation
+//! points to be the powers of a `2^k`-th root of unity so the interpolation is
+//! a radix-2 inverse FFT, which is `O(n log n)` instead of the `O(n^2)` of
+//! Lagrange interpolation.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::expression::CanonicalLinComb;
+use std::collections::BTreeMap;
+use zokrates_field::Field;
+
+/// A dense polynomial, coefficients in ascending degree order.
+pub type Polynomial<T> = Vec<T>;
+
+/// The three coefficient-form polynomial families of a QAP, indexed by witness
+/// variable, plus the size `domain` the constraints were padded to.
+pub struct Qap<T> {
+    pub a: BTreeMap<FlatVariable, Polynomial<T>>,
+    pub b: BTreeMap<FlatVariable, Polynomial<T>>,
+    pub c: BTreeMap<FlatVariable, Polynomial<T>>,
+    pub domain: usize,
+}
+
+/// In-place radix-2 Cooley-Tukey FFT over the subgroup generated by `root`,
+/// whose order must equal `values.len()` and be a power of two. Passing the
+/// inverse root (and scaling by `1/n`) performs the inverse transform.
+fn fft<T: Field>(values: &mut [T], root: &T) {
+    let n = values.len();
+    debug_assert!(n.is_power_of_two());
+
+    // bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        // w_len is a primitive len-th root of unity
+        let mut w_len = root.clone();
+        let mut e = n / len;
+        // w_len = root^(n/len)
+        let mut acc = T::one();
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = acc * w_len.clone();
+            }
+            w_len = w_len.clone() * w_len;
+            e >>= 1;
+        }
+        w_len = acc;
+
+        let mut i = 0;
+        while i < n {
+            let mut w = T::one();
+            for k in 0..len / 2 {
+                let u = values[i + k].clone();
+                let v = values[i + k + len / 2].clone() * w.clone();
+                values[i + k] = u.clone() + v.clone();
+                values[i + k + len / 2] = u - v;
+                w = w * w_len.clone();
+            }
+            i += len;
+        }
+        len <<= 1;
+    }
+}
+
+/// Interpolate `evaluations` (sampled on the `n`-th roots of unity) back to
+/// coefficient form via an inverse FFT. `root` is a primitive `n`-th root of
+/// unity with `n == evaluations.len()`.
+pub fn interpolate<T: Field>(mut evaluations: Vec<T>, root: &T) -> Polynomial<T> {
+    let n = evaluations.len();
+    let inv_root = root.clone().inverse_mul().unwrap();
+    fft(&mut evaluations, &inv_root);
+    let n_inv = T::from(n as u32).inverse_mul().unwrap();
+    evaluations.into_iter().map(|e| e * n_inv.clone()).collect()
+}
+
+/// Build the QAP polynomials from the R1CS matrices. Each matrix is given as a
+/// list of rows, one per constraint, each row a `CanonicalLinComb`. `root`
+/// must be a primitive root of unity whose order is exactly the domain size,
+/// the number of constraints rounded up to the next power of two.
+pub fn from_r1cs<T: Field>(
+    a_rows: &[CanonicalLinComb<T>],
+    b_rows: &[CanonicalLinComb<T>],
+    c_rows: &[CanonicalLinComb<T>],
+    root: &T,
+) -> Qap<T> {
+    let constraints = a_rows.len();
+    let domain = constraints.next_power_of_two().max(1);
+
+    Qap {
+        a: columns(a_rows, domain, root),
+        b: columns(b_rows, domain, root),
+        c: columns(c_rows, domain, root),
+        domain,
+    }
+}
+
+/// Transpose the per-constraint rows into per-variable evaluation vectors
+/// (padded to `domain`) and interpolate each into a polynomial.
+fn columns<T: Field>(
+    rows: &[CanonicalLinComb<T>],
+    domain: usize,
+    root: &T,
+) -> BTreeMap<FlatVariable, Polynomial<T>> {
+    let mut evaluations: BTreeMap<FlatVariable, Vec<T>> = BTreeMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        for (var, coeff) in &row.0 {
+            evaluations
+                .entry(*var)
+                .or_insert_with(|| vec![T::zero(); domain])[i] = coeff.clone();
+        }
+    }
+
+    evaluations
+        .into_iter()
+        .map(|(var, evals)| (var, interpolate(evals, root)))
+        .collect()
+}
@@ -0,0 +1,74 @@
+//! Witness evaluation and batched satisfiability checking.
+//!
+//! A rank-1 constraint has the shape `(a) * (b) == (c)`, where `a`, `b`, `c`
+//! are linear combinations of the witness variables. Evaluating the witness
+//! lets us check each constraint directly; checking them one by one is
+//! `O(constraints)` field multiplications. The batched check instead takes a
+//! random linear combination of all constraint residuals and verifies it is
+//! zero: by Schwartz-Zippel a single non-satisfied constraint makes this fail
+//! except with probability `(#constraints)/|F|`.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::expression::{LinComb, QuadComb};
+use std::collections::BTreeMap;
+use zokrates_field::Field;
+
+/// A concrete assignment of field values to witness variables.
+pub type Witness<T> = BTreeMap<FlatVariable, T>;
+
+/// A single rank-1 constraint `quad == lin`.
+pub struct Constraint<T> {
+    pub quad: QuadComb<T>,
+    pub lin: LinComb<T>,
+}
+
+/// Evaluate a linear combination against a witness. Unassigned variables are
+/// treated as zero, except `~one` which is always 1.
+pub fn eval_lin<T: Field>(lin: &LinComb<T>, witness: &Witness<T>) -> T {
+    lin.0.iter().fold(T::zero(), |acc, (var, coeff)| {
+        let value = if *var == FlatVariable::one() {
+            T::one()
+        } else {
+            witness.get(var).cloned().unwrap_or_else(T::zero)
+        };
+        acc + coeff.clone() * value
+    })
+}
+
+/// The residual `a * b - c` of a constraint under the witness; zero iff the
+/// constraint is satisfied.
+pub fn residual<T: Field>(c: &Constraint<T>, witness: &Witness<T>) -> T {
+    let a = eval_lin(&c.quad.left, witness);
+    let b = eval_lin(&c.quad.right, witness);
+    let out = eval_lin(&c.lin, witness);
+    a * b - out
+}
+
+/// Check every constraint individually, returning the index of the first
+/// unsatisfied one.
+pub fn check_all<T: Field>(constraints: &[Constraint<T>], witness: &Witness<T>) -> Result<(), usize> {
+    for (i, c) in constraints.iter().enumerate() {
+        if residual(c, witness) != T::zero() {
+            return Err(i);
+        }
+    }
+    Ok(())
+}
+
+/// Batched Schwartz-Zippel check: fold the residuals with increasing powers of
+/// `challenge` and verify the result is zero. `challenge` should be drawn at
+/// random (e.g. from a transcript) so a malicious or buggy witness cannot
+/// engineer a cancelling combination.
+pub fn check_batched<T: Field>(
+    constraints: &[Constraint<T>],
+    witness: &Witness<T>,
+    challenge: &T,
+) -> bool {
+    let mut power = T::one();
+    let mut acc = T::zero();
+    for c in constraints {
+        acc = acc + power.clone() * residual(c, witness);
+        power = power * challenge.clone();
+    }
+    acc == T::zero()
+}
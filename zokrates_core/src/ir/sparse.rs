@@ -0,0 +1,78 @@
+//! Sparse matrix export of a constraint system.
+//!
+//! Back-ends such as bellman consume the `A`, `B`, `C` matrices of an R1CS as
+//! sparse collections of `(row, column, coefficient)` triplets. This module
+//! assembles a matrix from the per-constraint linear combinations into
+//! coordinate (COO) form and converts it to compressed-sparse-row (CSR) form,
+//! which is what most linear-algebra and proving back-ends expect.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::expression::LinComb;
+use zokrates_field::Field;
+
+/// Coordinate-format sparse matrix: one triplet per non-zero entry.
+pub struct Coo<T> {
+    pub rows: usize,
+    pub cols: usize,
+    pub triplets: Vec<(usize, usize, T)>,
+}
+
+/// Compressed-sparse-row matrix: `row_ptr` has `rows + 1` entries delimiting
+/// each row's slice of `col_indices`/`values`.
+pub struct Csr<T> {
+    pub rows: usize,
+    pub cols: usize,
+    pub row_ptr: Vec<usize>,
+    pub col_indices: Vec<usize>,
+    pub values: Vec<T>,
+}
+
+impl<T: Field> Coo<T> {
+    /// Assemble a COO matrix from constraint rows. `cols` is the number of
+    /// witness variables; each variable maps to its column via `column_of`.
+    pub fn from_rows<F: Fn(&FlatVariable) -> usize>(
+        rows: &[LinComb<T>],
+        cols: usize,
+        column_of: F,
+    ) -> Self {
+        let mut triplets = vec![];
+        for (i, row) in rows.iter().enumerate() {
+            for (var, coeff) in &row.clone().into_canonical().0 {
+                triplets.push((i, column_of(var), coeff.clone()));
+            }
+        }
+
+        Coo {
+            rows: rows.len(),
+            cols,
+            triplets,
+        }
+    }
+
+    /// Convert to CSR, sorting entries by `(row, column)` so each row's columns
+    /// are ascending.
+    pub fn into_csr(mut self) -> Csr<T> {
+        self.triplets.sort_by_key(|(r, c, _)| (*r, *c));
+
+        let mut row_ptr = vec![0usize; self.rows + 1];
+        let mut col_indices = Vec::with_capacity(self.triplets.len());
+        let mut values = Vec::with_capacity(self.triplets.len());
+
+        for (r, c, v) in self.triplets {
+            row_ptr[r + 1] += 1;
+            col_indices.push(c);
+            values.push(v);
+        }
+        for i in 1..row_ptr.len() {
+            row_ptr[i] += row_ptr[i - 1];
+        }
+
+        Csr {
+            rows: self.rows,
+            cols: self.cols,
+            row_ptr,
+            col_indices,
+            values,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Compile-time folding of `IfElse` with a constant condition.
+//!
+//! Once constant propagation has reduced a condition to a literal
+//! `BooleanExpression::Value`, the whole conditional collapses to one of its
+//! branches. This pass performs that collapse for every expression kind, which
+//! both shrinks the AST and lets subsequent passes see through the branch that
+//! was taken.
+
+use crate::typed_absy::{folder::*, *};
+use zokrates_field::Field;
+
+pub struct IfElseFolder;
+
+impl IfElseFolder {
+    pub fn apply<'ast, T: Field>(p: TypedProgram<'ast, T>) -> TypedProgram<'ast, T> {
+        IfElseFolder.fold_program(p)
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for IfElseFolder {
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        match fold_field_expression(self, e) {
+            FieldElementExpression::IfElse(box BooleanExpression::Value(c), box a, box b) => {
+                if c {
+                    a
+                } else {
+                    b
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        match fold_boolean_expression(self, e) {
+            BooleanExpression::IfElse(box BooleanExpression::Value(c), box a, box b) => {
+                if c {
+                    a
+                } else {
+                    b
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match fold_uint_expression_inner(self, bitwidth, e) {
+            UExpressionInner::IfElse(box BooleanExpression::Value(c), box a, box b) => {
+                if c {
+                    a.into_inner()
+                } else {
+                    b.into_inner()
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_array_expression_inner(
+        &mut self,
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
+        match fold_array_expression_inner(self, ty, size, e) {
+            ArrayExpressionInner::IfElse(box BooleanExpression::Value(c), box a, box b) => {
+                if c {
+                    a.into_inner()
+                } else {
+                    b.into_inner()
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        match fold_struct_expression_inner(self, ty, e) {
+            StructExpressionInner::IfElse(box BooleanExpression::Value(c), box a, box b) => {
+                if c {
+                    a.into_inner()
+                } else {
+                    b.into_inner()
+                }
+            }
+            e => e,
+        }
+    }
+}
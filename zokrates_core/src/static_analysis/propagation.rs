@@ -0,0 +1,107 @@
+//! Constant folding and loop unrolling.
+//!
+//! A `Folder` pass that evaluates arithmetic and boolean expressions whose
+//! operands are already constant, drops the dead branch of a field-element
+//! `IfElse` with a constant condition, and unrolls `for` loops whose bounds are constant by
+//! substituting each concrete index value into a copy of the body.
+
+use crate::typed_absy::{folder::*, *};
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+pub struct Propagator<'ast, T: Field> {
+    /// Values of loop indices currently being unrolled.
+    constants: HashMap<Identifier<'ast>, T>,
+}
+
+impl<'ast, T: Field> Propagator<'ast, T> {
+    fn new() -> Self {
+        Propagator {
+            constants: HashMap::new(),
+        }
+    }
+
+    pub fn apply(p: TypedProgram<'ast, T>) -> TypedProgram<'ast, T> {
+        Propagator::new().fold_program(p)
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        match fold_field_expression(self, e) {
+            FieldElementExpression::Identifier(id) => match self.constants.get(&id) {
+                Some(v) => FieldElementExpression::Number(v.clone()),
+                None => FieldElementExpression::Identifier(id),
+            },
+            FieldElementExpression::Add(box FieldElementExpression::Number(a), box FieldElementExpression::Number(b)) => {
+                FieldElementExpression::Number(a + b)
+            }
+            FieldElementExpression::Sub(box FieldElementExpression::Number(a), box FieldElementExpression::Number(b)) => {
+                FieldElementExpression::Number(a - b)
+            }
+            FieldElementExpression::Mult(box FieldElementExpression::Number(a), box FieldElementExpression::Number(b)) => {
+                FieldElementExpression::Number(a * b)
+            }
+            FieldElementExpression::Div(box FieldElementExpression::Number(a), box FieldElementExpression::Number(b)) if b != T::zero() => {
+                FieldElementExpression::Number(a / b)
+            }
+            FieldElementExpression::IfElse(box BooleanExpression::Value(c), box consequence, box alternative) => {
+                if c {
+                    consequence
+                } else {
+                    alternative
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        match fold_boolean_expression(self, e) {
+            BooleanExpression::FieldEq(box FieldElementExpression::Number(a), box FieldElementExpression::Number(b)) => {
+                BooleanExpression::Value(a == b)
+            }
+            BooleanExpression::And(box BooleanExpression::Value(a), box BooleanExpression::Value(b)) => {
+                BooleanExpression::Value(a && b)
+            }
+            BooleanExpression::Or(box BooleanExpression::Value(a), box BooleanExpression::Value(b)) => {
+                BooleanExpression::Value(a || b)
+            }
+            BooleanExpression::Not(box BooleanExpression::Value(a)) => BooleanExpression::Value(!a),
+            e => e,
+        }
+    }
+
+    fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
+        match s {
+            TypedStatement::For(
+                var,
+                FieldElementExpression::Number(from),
+                FieldElementExpression::Number(to),
+                statements,
+            ) => {
+                let from = from.to_dec_string().parse::<usize>().unwrap_or(0);
+                let to = to.to_dec_string().parse::<usize>().unwrap_or(0);
+                let mut unrolled = vec![];
+                for i in from..to {
+                    self.constants.insert(var.id.clone(), i.into());
+                    unrolled.extend(
+                        statements
+                            .clone()
+                            .into_iter()
+                            .flat_map(|s| self.fold_statement(s)),
+                    );
+                }
+                self.constants.remove(&var.id);
+                unrolled
+            }
+            s => fold_statement(self, s),
+        }
+    }
+}
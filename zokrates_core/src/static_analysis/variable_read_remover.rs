@@ -7,25 +7,72 @@
 //!
 //! Would become
 //! ```zokrates
-//! if(index == 0, a[0], if(index == 1, a[1], ...))
+//! tmp = a
+//! assert(0 <= index && index < 3)
+//! if(index <= 1, if(index <= 0, tmp[0], tmp[1]), tmp[2])
 //! ```
+//!
+//! for a 3-element `a`. `select` picks the element by recursively bisecting the index range `0..size` rather than
+//! chaining one `if_else` per element: each level compares `index` against the midpoint of the
+//! remaining range and recurses into the matching half, so the tree has depth `O(log size)`
+//! instead of `O(size)`. Because every split narrows a known, static range, the two halves need
+//! no padding to stay balanced regardless of whether `size` is a power of two.
+//!
+//! A decompose-into-bits version of this (assert `index` equals a sum of `ceil(log2(size))`
+//! boolean witnesses, then multiplex pairs of elements per bit) would only need `O(log size)`
+//! comparisons instead of `O(log size)` recursive bisections over progressively smaller ranges,
+//! but it requires a way to introduce a witness whose value is supplied out-of-circuit (a `bit j
+//! of index`-style hint) and constrained after the fact. No such hint/directive mechanism exists
+//! at this typed-AST stage in this tree (nor anywhere below it, down to `ir`) — every variable
+//! here is bound by a `Definition` to a pure expression over already-bound values, and no
+//! `FieldElementExpression` variant can extract a bit of an arbitrary field element. Recursive
+//! bisection gets the same `O(log size)` depth using only the comparisons (`Le`) already
+//! supported by this AST, so it's used here instead.
 
 use crate::typed_absy::{folder::*, *};
 use zokrates_field::Field;
 
 pub struct VariableReadRemover<'ast, T: Field> {
     statements: Vec<TypedStatement<'ast, T>>,
+    // used to name the temporary array each dynamic `select` binds its source expression to, so
+    // two dynamic selects in the same program don't collide on the same identifier
+    count: usize,
 }
 
 impl<'ast, T: Field> VariableReadRemover<'ast, T> {
     fn new() -> Self {
-        Self { statements: vec![] }
+        Self {
+            statements: vec![],
+            count: 0,
+        }
     }
 
     pub fn apply(p: TypedProgram<'ast, T>) -> TypedProgram<'ast, T> {
         Self::new().fold_program(p)
     }
 
+    // recursively bisects `lo..=hi` (a sub-range of the array's indices), picking the half that
+    // contains `i` at each level; `lo == hi` is the base case, a single concrete element
+    fn balanced_select<U: Select<'ast, T> + IfElse<'ast, T>>(
+        a: &ArrayExpression<'ast, T>,
+        i: &FieldElementExpression<'ast, T>,
+        lo: usize,
+        hi: usize,
+    ) -> U {
+        if lo == hi {
+            U::select(a.clone(), FieldElementExpression::Number(lo.into()))
+        } else {
+            let mid = lo + (hi - lo) / 2;
+            let left = Self::balanced_select(a, i, lo, mid);
+            let right = Self::balanced_select(a, i, mid + 1, hi);
+            U::if_else(
+                BooleanExpression::Le(box i.clone(), box FieldElementExpression::Number(mid.into())),
+                left,
+                right,
+            )
+        }
+    }
+
     fn select<U: Select<'ast, T> + IfElse<'ast, T>>(
         &mut self,
         a: ArrayExpression<'ast, T>,
@@ -34,43 +81,48 @@ impl<'ast, T: Field> VariableReadRemover<'ast, T> {
         match i {
             FieldElementExpression::Number(i) => U::select(a, FieldElementExpression::Number(i)),
             i => {
-                let size = match a.get_type().clone() {
-                    Type::Array(array_ty) => array_ty.size,
+                let (elem_ty, size) = match a.get_type().clone() {
+                    Type::Array(array_ty) => (*array_ty.ty, array_ty.size),
                     _ => unreachable!(),
                 };
 
+                // bind `a` to a fresh identifier once, rather than cloning it at every leaf of
+                // the selection tree below: `a` may be a non-trivial expression (a function
+                // call, a nested select, a struct field), and cloning it per leaf would
+                // duplicate that whole subtree `O(size)` times
+                let id = Identifier::from(CoreIdentifier::Internal("select", self.count));
+                self.count += 1;
+                self.statements.push(TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::with_id_and_type(
+                        id.clone(),
+                        Type::array(elem_ty.clone(), size),
+                    )),
+                    a.into(),
+                ));
+                let a = ArrayExpressionInner::Identifier(id).annotate(elem_ty, size);
+
+                // a single range check replaces the old `n`-way disjunction of exact equalities,
+                // so out-of-range indices still fail regardless of which half of the tree below
+                // they would otherwise fall into
                 self.statements.push(TypedStatement::Assertion(
-                    (0..size)
-                        .map(|index| {
-                            BooleanExpression::FieldEq(
-                                box i.clone(),
-                                box FieldElementExpression::Number(index.into()).into(),
-                            )
-                        })
-                        .fold(None, |acc, e| match acc {
-                            Some(acc) => Some(BooleanExpression::Or(box acc, box e)),
-                            None => Some(e),
-                        })
-                        .unwrap()
-                        .into(),
+                    BooleanExpression::And(
+                        box BooleanExpression::Ge(
+                            box i.clone(),
+                            box FieldElementExpression::Number(0.into()),
+                        ),
+                        box BooleanExpression::Lt(
+                            box i.clone(),
+                            box FieldElementExpression::Number(size.into()),
+                        ),
+                    )
+                    .into(),
                 ));
 
-                (0..size)
-                    .map(|i| U::select(a.clone(), FieldElementExpression::Number(i.into())))
-                    .enumerate()
-                    .rev()
-                    .fold(None, |acc, (index, res)| match acc {
-                        Some(acc) => Some(U::if_else(
-                            BooleanExpression::FieldEq(
-                                box i.clone(),
-                                box FieldElementExpression::Number(index.into()),
-                            ),
-                            res,
-                            acc,
-                        )),
-                        None => Some(res),
-                    })
-                    .unwrap()
+                // `size - 1` below would underflow for an empty array; there is no valid index
+                // into it regardless of what `i` folds to, so fail deterministically instead
+                assert!(size > 0, "cannot select from an empty array");
+
+                Self::balanced_select(&a, &i, 0, size - 1)
             }
         }
     }
@@ -154,8 +206,9 @@ mod tests {
 
         // ->
 
-        // i <= 1 == true
-        // b = if i == 0 then a[0] else if i == 1 then a[1] else 0
+        // #INTERNAL#_select_0 = a
+        // assert(0 <= i && i < 2)
+        // b = if i <= 0 then #INTERNAL#_select_0[0] else #INTERNAL#_select_0[1]
 
         let access: TypedStatement<Bn128Field> = TypedStatement::Definition(
             TypedAssignee::Identifier(Variable::field_element("b")),
@@ -166,18 +219,29 @@ mod tests {
             .into(),
         );
 
+        let tmp = Identifier::from(CoreIdentifier::Internal("select", 0));
+
         assert_eq!(
             VariableReadRemover::new().fold_statement(access),
             vec![
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::with_id_and_type(
+                        tmp.clone(),
+                        Type::array(Type::FieldElement, 2)
+                    )),
+                    ArrayExpressionInner::Identifier("a".into())
+                        .annotate(Type::FieldElement, 2)
+                        .into(),
+                ),
                 TypedStatement::Assertion(
-                    BooleanExpression::Or(
-                        box BooleanExpression::FieldEq(
+                    BooleanExpression::And(
+                        box BooleanExpression::Ge(
                             box FieldElementExpression::Identifier("i".into()),
                             box FieldElementExpression::Number(0.into())
                         ),
-                        box BooleanExpression::FieldEq(
+                        box BooleanExpression::Lt(
                             box FieldElementExpression::Identifier("i".into()),
-                            box FieldElementExpression::Number(1.into())
+                            box FieldElementExpression::Number(2.into())
                         )
                     )
                     .into(),
@@ -185,17 +249,17 @@ mod tests {
                 TypedStatement::Definition(
                     TypedAssignee::Identifier(Variable::field_element("b")),
                     FieldElementExpression::if_else(
-                        BooleanExpression::FieldEq(
+                        BooleanExpression::Le(
                             box FieldElementExpression::Identifier("i".into()),
                             box FieldElementExpression::Number(0.into())
                         ),
                         FieldElementExpression::Select(
-                            box ArrayExpressionInner::Identifier("a".into())
+                            box ArrayExpressionInner::Identifier(tmp.clone())
                                 .annotate(Type::FieldElement, 2),
                             box FieldElementExpression::Number(0.into()),
                         ),
                         FieldElementExpression::Select(
-                            box ArrayExpressionInner::Identifier("a".into())
+                            box ArrayExpressionInner::Identifier(tmp)
                                 .annotate(Type::FieldElement, 2),
                             box FieldElementExpression::Number(1.into()),
                         )
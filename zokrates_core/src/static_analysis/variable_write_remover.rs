@@ -0,0 +1,323 @@
+//! Module containing removal of variable write access to complex types
+//!
+//! For example:
+//! ```zokrates
+//! a[index] = v
+//! ```
+//!
+//! Would become
+//! ```zokrates
+//! tmp = a
+//! assert(0 <= index && index < size)
+//! a = [if j == index then v else tmp[j] for j in 0..size]
+//! ```
+//!
+//! This is the write-side counterpart to `VariableReadRemover`: that pass rewrites a dynamic
+//! read `a[i]`, but leaves a dynamic write `a[i] = v` unchanged, since writing through a
+//! `Select`-shaped assignee isn't an expression `fold_*` ever visits. This pass instead looks at
+//! the assignee of a `Definition`, and when it's a `Select` with a non-constant index, replaces
+//! the statement with a redefinition of the whole array. `a`'s current value is bound to a
+//! temporary once before expanding the `size` `if_else` branches, for the same reason
+//! `VariableReadRemover` binds its source array: cloning a non-trivial expression per branch
+//! would duplicate it `O(size)` times.
+
+use crate::typed_absy::{folder::*, *};
+use zokrates_field::Field;
+
+pub struct VariableWriteRemover<'ast, T: Field> {
+    statements: Vec<TypedStatement<'ast, T>>,
+    // used to name the temporary array each dynamic write binds its base expression to, so two
+    // dynamic writes in the same program don't collide on the same identifier
+    count: usize,
+}
+
+impl<'ast, T: Field> VariableWriteRemover<'ast, T> {
+    fn new() -> Self {
+        Self {
+            statements: vec![],
+            count: 0,
+        }
+    }
+
+    pub fn apply(p: TypedProgram<'ast, T>) -> TypedProgram<'ast, T> {
+        Self::new().fold_program(p)
+    }
+
+    // read the current value held by a typed lvalue, the way `semantics::Checker` does for
+    // compound assignment, so writing to one element of it can be desugared into a
+    // redefinition built out of the rest of its current value
+    fn assignee_to_expression(a: &TypedAssignee<'ast, T>) -> TypedExpression<'ast, T> {
+        match a {
+            TypedAssignee::Identifier(v) => match v.get_type() {
+                Type::FieldElement => FieldElementExpression::Identifier(v.id.clone()).into(),
+                Type::Boolean => BooleanExpression::Identifier(v.id.clone()).into(),
+                Type::Uint(bitwidth) => UExpressionInner::Identifier(v.id.clone())
+                    .annotate(bitwidth)
+                    .into(),
+                Type::Array(array_type) => ArrayExpressionInner::Identifier(v.id.clone())
+                    .annotate(*array_type.ty, array_type.size)
+                    .into(),
+                Type::Struct(members) => StructExpressionInner::Identifier(v.id.clone())
+                    .annotate(members)
+                    .into(),
+            },
+            TypedAssignee::Select(box a, box index) => match Self::assignee_to_expression(a) {
+                TypedExpression::Array(a) => match a.inner_type().clone() {
+                    Type::FieldElement => FieldElementExpression::select(a, index.clone()).into(),
+                    Type::Boolean => BooleanExpression::select(a, index.clone()).into(),
+                    Type::Uint(..) => UExpression::select(a, index.clone()).into(),
+                    Type::Array(..) => ArrayExpression::select(a, index.clone()).into(),
+                    Type::Struct(..) => StructExpression::select(a, index.clone()).into(),
+                },
+                e => unreachable!(
+                    "assignee {} should type-check to an array, found {}",
+                    e,
+                    e.get_type()
+                ),
+            },
+            TypedAssignee::Member(box s, id) => match Self::assignee_to_expression(s) {
+                TypedExpression::Struct(s) => {
+                    let ty = s
+                        .ty()
+                        .iter()
+                        .find(|m| m.id == *id)
+                        .map(|m| *m.ty.clone())
+                        .unwrap();
+                    match ty {
+                        Type::FieldElement => FieldElementExpression::member(s, id.clone()).into(),
+                        Type::Boolean => BooleanExpression::member(s, id.clone()).into(),
+                        Type::Uint(..) => UExpression::member(s, id.clone()).into(),
+                        Type::Array(array_type) => {
+                            ArrayExpressionInner::Member(box s.clone(), id.clone())
+                                .annotate(*array_type.ty, array_type.size)
+                                .into()
+                        }
+                        Type::Struct(members) => {
+                            StructExpressionInner::Member(box s.clone(), id.clone())
+                                .annotate(members)
+                                .into()
+                        }
+                    }
+                }
+                e => unreachable!(
+                    "assignee {} should type-check to a struct, found {}",
+                    e,
+                    e.get_type()
+                ),
+            },
+            TypedAssignee::Slice(..) => {
+                unreachable!("a slice is not a valid base for a dynamic element write")
+            }
+        }
+    }
+
+    // builds `[if j == index then value else base[j] for j in 0..size]`, one `IfElse`/`Select`
+    // pair per element, for whichever concrete element type `U` the array holds
+    fn rewrite<U: Select<'ast, T> + IfElse<'ast, T> + Clone>(
+        base: ArrayExpression<'ast, T>,
+        index: FieldElementExpression<'ast, T>,
+        value: U,
+        size: usize,
+    ) -> Vec<TypedExpression<'ast, T>>
+    where
+        TypedExpression<'ast, T>: From<U>,
+    {
+        (0..size)
+            .map(|j| {
+                U::if_else(
+                    BooleanExpression::FieldEq(
+                        box FieldElementExpression::Number(j.into()),
+                        box index.clone(),
+                    ),
+                    value.clone(),
+                    U::select(base.clone(), FieldElementExpression::Number(j.into())),
+                )
+                .into()
+            })
+            .collect()
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for VariableWriteRemover<'ast, T> {
+    fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
+        match s {
+            TypedStatement::Definition(TypedAssignee::Select(box base, box index), value) => {
+                match index {
+                    FieldElementExpression::Number(n) => fold_statement(
+                        self,
+                        TypedStatement::Definition(
+                            TypedAssignee::Select(
+                                box base,
+                                box FieldElementExpression::Number(n),
+                            ),
+                            value,
+                        ),
+                    ),
+                    index => {
+                        let array = match Self::assignee_to_expression(&base) {
+                            TypedExpression::Array(a) => a,
+                            e => unreachable!(
+                                "assignee {} should type-check to an array, found {}",
+                                e,
+                                e.get_type()
+                            ),
+                        };
+
+                        let ty = array.inner_type().clone();
+                        let size = array.size();
+
+                        // bind the current value to a fresh identifier once, rather than
+                        // cloning it into every one of the `size` `if_else` branches below:
+                        // `array` may be a non-trivial expression (a function call, a nested
+                        // select, a struct field), and cloning it per branch would duplicate
+                        // that whole subtree `O(size)` times
+                        let id = Identifier::from(CoreIdentifier::Internal("write", self.count));
+                        self.count += 1;
+                        self.statements.push(TypedStatement::Definition(
+                            TypedAssignee::Identifier(Variable::with_id_and_type(
+                                id.clone(),
+                                Type::array(ty.clone(), size),
+                            )),
+                            array.into(),
+                        ));
+                        let array = ArrayExpressionInner::Identifier(id).annotate(ty.clone(), size);
+
+                        self.statements.push(TypedStatement::Assertion(
+                            BooleanExpression::And(
+                                box BooleanExpression::Ge(
+                                    box index.clone(),
+                                    box FieldElementExpression::Number(0.into()),
+                                ),
+                                box BooleanExpression::Lt(
+                                    box index.clone(),
+                                    box FieldElementExpression::Number(size.into()),
+                                ),
+                            )
+                            .into(),
+                        ));
+
+                        let values = match (ty.clone(), value) {
+                            (Type::FieldElement, TypedExpression::FieldElement(v)) => {
+                                Self::rewrite(array, index, v, size)
+                            }
+                            (Type::Boolean, TypedExpression::Boolean(v)) => {
+                                Self::rewrite(array, index, v, size)
+                            }
+                            (Type::Uint(..), TypedExpression::Uint(v)) => {
+                                Self::rewrite(array, index, v, size)
+                            }
+                            (Type::Array(..), TypedExpression::Array(v)) => {
+                                Self::rewrite(array, index, v, size)
+                            }
+                            (Type::Struct(..), TypedExpression::Struct(v)) => {
+                                Self::rewrite(array, index, v, size)
+                            }
+                            (ty, v) => unreachable!(
+                                "value {} assigned into an array of {} should have type {}, found {}",
+                                v,
+                                ty,
+                                ty,
+                                v.get_type()
+                            ),
+                        };
+
+                        let new_array =
+                            ArrayExpressionInner::Value(values).annotate(ty, size);
+
+                        let s = fold_statement(self, TypedStatement::Definition(base, new_array.into()));
+                        self.statements.drain(..).chain(s).collect()
+                    }
+                }
+            }
+            s => fold_statement(self, s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn select() {
+        // a[i] = v
+
+        // ->
+
+        // #INTERNAL#_write_0 = a
+        // assert(0 <= i && i < 2)
+        // a = [if 0 == i then v else #INTERNAL#_write_0[0], if 1 == i then v else #INTERNAL#_write_0[1]]
+
+        let write: TypedStatement<Bn128Field> = TypedStatement::Definition(
+            TypedAssignee::Select(
+                box TypedAssignee::Identifier(Variable::field_array("a", 2)),
+                box FieldElementExpression::Identifier("i".into()),
+            ),
+            FieldElementExpression::Identifier("v".into()).into(),
+        );
+
+        let tmp = Identifier::from(CoreIdentifier::Internal("write", 0));
+
+        assert_eq!(
+            VariableWriteRemover::new().fold_statement(write),
+            vec![
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::with_id_and_type(
+                        tmp.clone(),
+                        Type::array(Type::FieldElement, 2)
+                    )),
+                    ArrayExpressionInner::Identifier("a".into())
+                        .annotate(Type::FieldElement, 2)
+                        .into(),
+                ),
+                TypedStatement::Assertion(
+                    BooleanExpression::And(
+                        box BooleanExpression::Ge(
+                            box FieldElementExpression::Identifier("i".into()),
+                            box FieldElementExpression::Number(0.into())
+                        ),
+                        box BooleanExpression::Lt(
+                            box FieldElementExpression::Identifier("i".into()),
+                            box FieldElementExpression::Number(2.into())
+                        )
+                    )
+                    .into(),
+                ),
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::field_array("a", 2)),
+                    ArrayExpressionInner::Value(vec![
+                        FieldElementExpression::if_else(
+                            BooleanExpression::FieldEq(
+                                box FieldElementExpression::Number(0.into()),
+                                box FieldElementExpression::Identifier("i".into())
+                            ),
+                            FieldElementExpression::Identifier("v".into()),
+                            FieldElementExpression::Select(
+                                box ArrayExpressionInner::Identifier(tmp.clone())
+                                    .annotate(Type::FieldElement, 2),
+                                box FieldElementExpression::Number(0.into()),
+                            ),
+                        )
+                        .into(),
+                        FieldElementExpression::if_else(
+                            BooleanExpression::FieldEq(
+                                box FieldElementExpression::Number(1.into()),
+                                box FieldElementExpression::Identifier("i".into())
+                            ),
+                            FieldElementExpression::Identifier("v".into()),
+                            FieldElementExpression::Select(
+                                box ArrayExpressionInner::Identifier(tmp)
+                                    .annotate(Type::FieldElement, 2),
+                                box FieldElementExpression::Number(1.into()),
+                            ),
+                        )
+                        .into(),
+                    ])
+                    .annotate(Type::FieldElement, 2)
+                    .into()
+                )
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Hierarchical symbol tables with fully-qualified names.
+//!
+//! The semantic checker resolves names against a flat `HashSet` of variables
+//! plus a set of function keys, which makes nested scopes and qualified module
+//! paths awkward. This module provides a scoped symbol table keyed by
+//! fully-qualified name (module path + local name), backed by a prefix trie so
+//! that prefix queries — completion, and the edit-distance "did you mean …"
+//! suggestions — can enumerate candidates cheaply.
+
+use std::collections::HashMap;
+
+/// A fully-qualified name: an ordered path of segments, e.g.
+/// `["std", "hashes", "sha256"]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QualifiedName(pub Vec<String>);
+
+impl QualifiedName {
+    pub fn new<S: Into<String>>(segments: impl IntoIterator<Item = S>) -> Self {
+        QualifiedName(segments.into_iter().map(Into::into).collect())
+    }
+
+    /// Append a local name to a module path.
+    pub fn child<S: Into<String>>(&self, name: S) -> QualifiedName {
+        let mut segments = self.0.clone();
+        segments.push(name.into());
+        QualifiedName(segments)
+    }
+
+    pub fn to_string(&self) -> String {
+        self.0.join("::")
+    }
+}
+
+/// A scoped symbol table. Entering a scope pushes a frame; leaving it pops the
+/// frame and forgets its symbols. Lookups walk frames from innermost to
+/// outermost, so inner declarations shadow outer ones.
+pub struct SymbolTable<V> {
+    scopes: Vec<HashMap<QualifiedName, V>>,
+    trie: Trie,
+}
+
+impl<V> SymbolTable<V> {
+    pub fn new() -> Self {
+        SymbolTable {
+            scopes: vec![HashMap::new()],
+            trie: Trie::default(),
+        }
+    }
+
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn exit_scope(&mut self) {
+        // the root scope is never popped
+        if self.scopes.len() > 1 {
+            let frame = self.scopes.pop().unwrap();
+            for name in frame.keys() {
+                self.trie.remove(&name.to_string());
+            }
+        }
+    }
+
+    /// Insert a symbol in the current (innermost) scope.
+    pub fn insert(&mut self, name: QualifiedName, value: V) {
+        self.trie.insert(&name.to_string());
+        self.scopes.last_mut().unwrap().insert(name, value);
+    }
+
+    /// Resolve a name, innermost scope first.
+    pub fn lookup(&self, name: &QualifiedName) -> Option<&V> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// All fully-qualified names sharing the given prefix, across every scope.
+    pub fn with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.trie.with_prefix(prefix)
+    }
+
+    /// The in-scope name closest to `name` by edit distance, for a "did you
+    /// mean …" hint on an unresolved symbol. Returns `None` when nothing is
+    /// within a sensible threshold.
+    pub fn suggest(&self, name: &str) -> Option<String> {
+        closest(name, self.trie.with_prefix(""))
+    }
+}
+
+/// Damerau-free Levenshtein edit distance between two strings.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// The candidate closest to `name` by edit distance, if one lies within a
+/// threshold scaled to the name's length (so short names only match very close
+/// typos).
+pub fn closest<I: IntoIterator<Item = String>>(name: &str, candidates: I) -> Option<String> {
+    let threshold = (name.len() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|c| {
+            let d = edit_distance(name, &c);
+            (d, c)
+        })
+        .filter(|(d, _)| *d <= threshold)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+impl<V> Default for SymbolTable<V> {
+    fn default() -> Self {
+        SymbolTable::new()
+    }
+}
+
+/// A character trie over the stringified qualified names currently in scope.
+#[derive(Default)]
+struct Trie {
+    root: TrieNode,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<char, TrieNode>,
+    /// How many inserted names terminate here (a name may be inserted in more
+    /// than one scope at once).
+    terminals: usize,
+}
+
+impl Trie {
+    fn insert(&mut self, key: &str) {
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.terminals += 1;
+    }
+
+    fn remove(&mut self, key: &str) {
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            match node.children.get_mut(&c) {
+                Some(next) => node = next,
+                None => return,
+            }
+        }
+        if node.terminals > 0 {
+            node.terminals -= 1;
+        }
+    }
+
+    fn with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut node = &self.root;
+        for c in prefix.chars() {
+            match node.children.get(&c) {
+                Some(next) => node = next,
+                None => return vec![],
+            }
+        }
+        let mut out = vec![];
+        collect(node, prefix.to_string(), &mut out);
+        out
+    }
+}
+
+fn collect(node: &TrieNode, prefix: String, out: &mut Vec<String>) {
+    if node.terminals > 0 {
+        out.push(prefix.clone());
+    }
+    for (c, child) in &node.children {
+        let mut next = prefix.clone();
+        next.push(*c);
+        collect(child, next, out);
+    }
+}
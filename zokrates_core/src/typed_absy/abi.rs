@@ -1,5 +1,9 @@
+use crate::typed_absy::types::UBitwidth;
 use crate::typed_absy::{Signature, Type};
+use num_bigint::BigUint;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use zokrates_field::Field;
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct AbiInput {
@@ -17,6 +21,37 @@ pub struct Abi {
     pub outputs: Vec<AbiOutput>,
 }
 
+/// A concrete value bound to an ABI-typed input or output, as parsed from (or about to be
+/// serialized to) a JSON document by [`Abi::parse_inputs`]/[`Abi::encode_outputs`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue<T> {
+    Field(T),
+    Boolean(bool),
+    Uint(u128, UBitwidth),
+    Array(Vec<TypedValue<T>>),
+    /// Members in declaration order, alongside their name.
+    Struct(Vec<(String, TypedValue<T>)>),
+}
+
+/// An error raised while binding a JSON document (or a flat field-element vector) to an [`Abi`],
+/// carrying a message that names the offending path, e.g. `` `foo.bar[2]` should be a boolean ``.
+#[derive(Debug, PartialEq)]
+pub struct AbiError(String);
+
+impl AbiError {
+    fn new<S: Into<String>>(message: S) -> Self {
+        AbiError(message.into())
+    }
+}
+
+impl fmt::Display for AbiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for AbiError {}
+
 impl Abi {
     pub fn signature(&self) -> Signature {
         Signature {
@@ -24,6 +59,290 @@ impl Abi {
             outputs: self.outputs.clone(),
         }
     }
+
+    /// Binds a JSON value per input, in argument order, to this ABI's declared input types.
+    pub fn parse_inputs<T: Field>(
+        &self,
+        values: &[serde_json::Value],
+    ) -> Result<Vec<TypedValue<T>>, AbiError> {
+        if values.len() != self.inputs.len() {
+            return Err(AbiError::new(format!(
+                "expected {} argument(s), found {}",
+                self.inputs.len(),
+                values.len()
+            )));
+        }
+
+        self.inputs
+            .iter()
+            .zip(values)
+            .map(|(input, value)| parse_value(&input.ty, value, &input.name))
+            .collect()
+    }
+
+    /// The inverse of `parse_inputs`, for the values a witness computation produced for this
+    /// ABI's declared outputs.
+    pub fn encode_outputs<T: Field>(
+        &self,
+        values: &[TypedValue<T>],
+    ) -> Result<Vec<serde_json::Value>, AbiError> {
+        if values.len() != self.outputs.len() {
+            return Err(AbiError::new(format!(
+                "expected {} output(s), found {}",
+                self.outputs.len(),
+                values.len()
+            )));
+        }
+
+        self.outputs
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(i, (ty, value))| encode_value(ty, value, &format!("~out{}", i)))
+            .collect()
+    }
+
+    /// Flattens `values` (bound to `self.inputs`, in order) into the flat field-element vector a
+    /// proving/verifying backend expects: arrays expand row-major, struct members in declaration
+    /// order, booleans to 0/1, and uints to their field encoding.
+    pub fn flatten_inputs<T: Field>(&self, values: &[TypedValue<T>]) -> Vec<T> {
+        values.iter().flat_map(flatten_value).collect()
+    }
+
+    /// The same flattening as `flatten_inputs`, for `self.outputs`.
+    pub fn flatten_outputs<T: Field>(&self, values: &[TypedValue<T>]) -> Vec<T> {
+        values.iter().flat_map(flatten_value).collect()
+    }
+
+    /// The subset of `flatten_inputs`'s output that corresponds to `public` inputs, in the same
+    /// order, for a Solidity-style verifier export to map onto its on-chain arguments.
+    pub fn public_inputs<T: Field>(&self, values: &[TypedValue<T>]) -> Vec<T> {
+        self.inputs
+            .iter()
+            .zip(values)
+            .filter(|(input, _)| input.public)
+            .flat_map(|(_, value)| flatten_value(value))
+            .collect()
+    }
+
+    /// The inverse of `flatten_inputs`: reconstructs typed values from a flat field-element
+    /// vector, using `self.inputs` as the schema.
+    pub fn unflatten_inputs<T: Field>(&self, flat: &[T]) -> Result<Vec<TypedValue<T>>, AbiError> {
+        unflatten(self.inputs.iter().map(|i| &i.ty), flat)
+    }
+
+    /// The inverse of `flatten_outputs`, using `self.outputs` as the schema.
+    pub fn unflatten_outputs<T: Field>(&self, flat: &[T]) -> Result<Vec<TypedValue<T>>, AbiError> {
+        unflatten(self.outputs.iter(), flat)
+    }
+}
+
+fn flatten_value<T: Field>(value: &TypedValue<T>) -> Vec<T> {
+    match value {
+        TypedValue::Field(f) => vec![f.clone()],
+        TypedValue::Boolean(b) => vec![if *b { T::one() } else { T::zero() }],
+        TypedValue::Uint(n, _) => vec![T::try_from(BigUint::from(*n))
+            .unwrap_or_else(|_| unreachable!("a value that fits its declared bit-width always fits the field"))],
+        TypedValue::Array(values) => values.iter().flat_map(flatten_value).collect(),
+        TypedValue::Struct(members) => members.iter().flat_map(|(_, v)| flatten_value(v)).collect(),
+    }
+}
+
+fn unflatten<'a, T: Field>(
+    tys: impl Iterator<Item = &'a Type>,
+    flat: &[T],
+) -> Result<Vec<TypedValue<T>>, AbiError> {
+    let mut cursor = 0;
+    let values = tys
+        .map(|ty| unflatten_value(ty, flat, &mut cursor))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if cursor != flat.len() {
+        return Err(AbiError::new(format!(
+            "expected {} field element(s), found {}",
+            cursor,
+            flat.len()
+        )));
+    }
+
+    Ok(values)
+}
+
+fn unflatten_value<T: Field>(ty: &Type, flat: &[T], cursor: &mut usize) -> Result<TypedValue<T>, AbiError> {
+    match ty {
+        Type::Array(array_type) => (0..array_type.size)
+            .map(|_| unflatten_value(&array_type.ty, flat, cursor))
+            .collect::<Result<_, _>>()
+            .map(TypedValue::Array),
+        Type::Struct(struct_type) => struct_type
+            .iter()
+            .map(|member| unflatten_value(&member.ty, flat, cursor).map(|v| (member.id.clone(), v)))
+            .collect::<Result<_, _>>()
+            .map(TypedValue::Struct),
+        ty => {
+            let f = flat.get(*cursor).cloned().ok_or_else(|| {
+                AbiError::new("not enough field elements to reconstruct this value")
+            })?;
+            *cursor += 1;
+
+            Ok(match ty {
+                Type::FieldElement => TypedValue::Field(f),
+                Type::Boolean => TypedValue::Boolean(f != T::zero()),
+                Type::Uint(bitwidth) => TypedValue::Uint(
+                    f.to_dec_string().parse::<u128>().unwrap_or(0),
+                    *bitwidth,
+                ),
+                _ => unreachable!(),
+            })
+        }
+    }
+}
+
+fn parse_value<T: Field>(
+    ty: &Type,
+    value: &serde_json::Value,
+    path: &str,
+) -> Result<TypedValue<T>, AbiError> {
+    match ty {
+        Type::FieldElement => {
+            let s = value.as_str().ok_or_else(|| {
+                AbiError::new(format!(
+                    "`{}` should be a field element encoded as a decimal string, found {}",
+                    path, value
+                ))
+            })?;
+            let n = s.parse::<BigUint>().map_err(|_| {
+                AbiError::new(format!(
+                    "`{}` is not a valid decimal number, found \"{}\"",
+                    path, s
+                ))
+            })?;
+            let f = T::try_from(n).map_err(|_| {
+                AbiError::new(format!(
+                    "`{}` is out of the field's representable range [{}, {}]",
+                    path,
+                    T::min_value(),
+                    T::max_value()
+                ))
+            })?;
+            Ok(TypedValue::Field(f))
+        }
+        Type::Boolean => value
+            .as_bool()
+            .map(TypedValue::Boolean)
+            .ok_or_else(|| AbiError::new(format!("`{}` should be a boolean, found {}", path, value))),
+        Type::Uint(bitwidth) => {
+            let n = value.as_u64().ok_or_else(|| {
+                AbiError::new(format!(
+                    "`{}` should be an unsigned integer, found {}",
+                    path, value
+                ))
+            })? as u128;
+
+            let width = uint_width(*bitwidth);
+            if n >= 1u128 << width {
+                return Err(AbiError::new(format!(
+                    "`{}` does not fit in {} bits, found {}",
+                    path, width, n
+                )));
+            }
+
+            Ok(TypedValue::Uint(n, *bitwidth))
+        }
+        Type::Array(array_type) => {
+            let values = value.as_array().ok_or_else(|| {
+                AbiError::new(format!("`{}` should be an array, found {}", path, value))
+            })?;
+
+            if values.len() != array_type.size {
+                return Err(AbiError::new(format!(
+                    "`{}` should have {} element(s), found {}",
+                    path, array_type.size, values.len()
+                )));
+            }
+
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| parse_value(&array_type.ty, v, &format!("{}[{}]", path, i)))
+                .collect::<Result<_, _>>()
+                .map(TypedValue::Array)
+        }
+        Type::Struct(struct_type) => {
+            let object = value.as_object().ok_or_else(|| {
+                AbiError::new(format!("`{}` should be an object, found {}", path, value))
+            })?;
+
+            struct_type
+                .iter()
+                .map(|member| {
+                    let field_path = format!("{}.{}", path, member.id);
+                    let v = object.get(&member.id).ok_or_else(|| {
+                        AbiError::new(format!("`{}` is missing field `{}`", path, member.id))
+                    })?;
+                    parse_value(&member.ty, v, &field_path).map(|v| (member.id.clone(), v))
+                })
+                .collect::<Result<_, _>>()
+                .map(TypedValue::Struct)
+        }
+    }
+}
+
+fn encode_value<T: Field>(
+    ty: &Type,
+    value: &TypedValue<T>,
+    path: &str,
+) -> Result<serde_json::Value, AbiError> {
+    match (ty, value) {
+        (Type::FieldElement, TypedValue::Field(f)) => {
+            Ok(serde_json::Value::String(f.to_dec_string()))
+        }
+        (Type::Boolean, TypedValue::Boolean(b)) => Ok(serde_json::Value::Bool(*b)),
+        (Type::Uint(bitwidth), TypedValue::Uint(n, value_bitwidth)) if bitwidth == value_bitwidth => {
+            Ok(serde_json::Value::Number((*n as u64).into()))
+        }
+        (Type::Array(array_type), TypedValue::Array(values)) => {
+            if values.len() != array_type.size {
+                return Err(AbiError::new(format!(
+                    "`{}` should have {} element(s), found {}",
+                    path, array_type.size, values.len()
+                )));
+            }
+
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| encode_value(&array_type.ty, v, &format!("{}[{}]", path, i)))
+                .collect::<Result<_, _>>()
+                .map(serde_json::Value::Array)
+        }
+        (Type::Struct(struct_type), TypedValue::Struct(members)) => {
+            let mut object = serde_json::Map::new();
+            for member in struct_type.iter() {
+                let field_path = format!("{}.{}", path, member.id);
+                let (_, v) = members
+                    .iter()
+                    .find(|(name, _)| name == &member.id)
+                    .ok_or_else(|| {
+                        AbiError::new(format!("`{}` is missing field `{}`", path, member.id))
+                    })?;
+                object.insert(member.id.clone(), encode_value(&member.ty, v, &field_path)?);
+            }
+            Ok(serde_json::Value::Object(object))
+        }
+        (ty, _) => Err(AbiError::new(format!(
+            "`{}` should be a {}, found a value of a different shape",
+            path, ty
+        ))),
+    }
+}
+
+fn uint_width(bitwidth: UBitwidth) -> u32 {
+    match bitwidth {
+        UBitwidth::B8 => 8,
+        UBitwidth::B16 => 16,
+        UBitwidth::B32 => 32,
+    }
 }
 
 #[cfg(test)]
@@ -444,4 +763,184 @@ mod tests {
         let de_abi: Abi = serde_json::from_str(json.as_ref()).unwrap();
         assert_eq!(de_abi, abi);
     }
+
+    #[test]
+    fn parse_valid_inputs() {
+        let abi: Abi = Abi {
+            inputs: vec![
+                AbiInput {
+                    name: String::from("a"),
+                    public: true,
+                    ty: Type::FieldElement,
+                },
+                AbiInput {
+                    name: String::from("b"),
+                    public: true,
+                    ty: Type::Uint(UBitwidth::B8),
+                },
+                AbiInput {
+                    name: String::from("c"),
+                    public: true,
+                    ty: Type::array(Type::Boolean, 2),
+                },
+            ],
+            outputs: vec![],
+        };
+
+        let values: Vec<serde_json::Value> = serde_json::from_str(
+            r#"["42", 255, [true, false]]"#,
+        )
+        .unwrap();
+
+        let parsed = abi.parse_inputs::<Bn128Field>(&values).unwrap();
+
+        assert_eq!(
+            parsed,
+            vec![
+                TypedValue::Field(Bn128Field::try_from(BigUint::from(42u32)).unwrap()),
+                TypedValue::Uint(255, UBitwidth::B8),
+                TypedValue::Array(vec![
+                    TypedValue::Boolean(true),
+                    TypedValue::Boolean(false)
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_wrong_argument_count() {
+        let abi: Abi = Abi {
+            inputs: vec![AbiInput {
+                name: String::from("a"),
+                public: true,
+                ty: Type::FieldElement,
+            }],
+            outputs: vec![],
+        };
+
+        let values: Vec<serde_json::Value> = vec![];
+        assert!(abi.parse_inputs::<Bn128Field>(&values).is_err());
+    }
+
+    #[test]
+    fn parse_uint_overflow() {
+        let abi: Abi = Abi {
+            inputs: vec![AbiInput {
+                name: String::from("a"),
+                public: true,
+                ty: Type::Uint(UBitwidth::B8),
+            }],
+            outputs: vec![],
+        };
+
+        let values: Vec<serde_json::Value> = serde_json::from_str(r#"[256]"#).unwrap();
+        let error = abi.parse_inputs::<Bn128Field>(&values).unwrap_err();
+        assert_eq!(
+            error,
+            AbiError::new("`a` does not fit in 8 bits, found 256")
+        );
+    }
+
+    #[test]
+    fn parse_struct_reports_qualified_path() {
+        let abi: Abi = Abi {
+            inputs: vec![AbiInput {
+                name: String::from("foo"),
+                public: true,
+                ty: Type::Struct(StructType::new(
+                    "".into(),
+                    "Foo".into(),
+                    vec![StructMember::new("bar".into(), Type::array(Type::FieldElement, 3))],
+                )),
+            }],
+            outputs: vec![],
+        };
+
+        let values: Vec<serde_json::Value> =
+            serde_json::from_str(r#"[{"bar": ["0", "1", true]}]"#).unwrap();
+
+        let error = abi.parse_inputs::<Bn128Field>(&values).unwrap_err();
+        assert_eq!(
+            error,
+            AbiError::new(
+                "`foo.bar[2]` should be a field element encoded as a decimal string, found true"
+            )
+        );
+    }
+
+    #[test]
+    fn encode_outputs_to_json() {
+        let abi: Abi = Abi {
+            inputs: vec![],
+            outputs: vec![Type::Boolean, Type::Uint(UBitwidth::B32)],
+        };
+
+        let values = vec![
+            TypedValue::Boolean(true),
+            TypedValue::Uint(42, UBitwidth::B32),
+        ];
+
+        let encoded = abi.encode_outputs::<Bn128Field>(&values).unwrap();
+        assert_eq!(
+            encoded,
+            vec![serde_json::Value::Bool(true), serde_json::json!(42)]
+        );
+    }
+
+    #[test]
+    fn flatten_and_unflatten_inputs() {
+        let abi: Abi = Abi {
+            inputs: vec![
+                AbiInput {
+                    name: String::from("a"),
+                    public: false,
+                    ty: Type::FieldElement,
+                },
+                AbiInput {
+                    name: String::from("b"),
+                    public: true,
+                    ty: Type::array(Type::Boolean, 2),
+                },
+            ],
+            outputs: vec![],
+        };
+
+        let values = vec![
+            TypedValue::Field(Bn128Field::from(42)),
+            TypedValue::Array(vec![
+                TypedValue::Boolean(true),
+                TypedValue::Boolean(false),
+            ]),
+        ];
+
+        let flat = abi.flatten_inputs(&values);
+        assert_eq!(
+            flat,
+            vec![
+                Bn128Field::from(42),
+                Bn128Field::from(1),
+                Bn128Field::from(0)
+            ]
+        );
+
+        assert_eq!(abi.public_inputs(&values), vec![Bn128Field::from(1), Bn128Field::from(0)]);
+
+        assert_eq!(abi.unflatten_inputs(&flat).unwrap(), values);
+    }
+
+    #[test]
+    fn unflatten_wrong_length() {
+        let abi: Abi = Abi {
+            inputs: vec![AbiInput {
+                name: String::from("a"),
+                public: true,
+                ty: Type::FieldElement,
+            }],
+            outputs: vec![],
+        };
+
+        assert!(abi
+            .unflatten_inputs::<Bn128Field>(&[Bn128Field::from(1), Bn128Field::from(2)])
+            .is_err());
+    }
 }
@@ -0,0 +1,420 @@
+//! A tree-walking evaluator over the typed AST.
+//!
+//! Given concrete values for a function's inputs, this walks
+//! `FieldElementExpression`/`BooleanExpression`/array expressions and the
+//! statement list and computes the resulting values. `UExpression`s are not
+//! evaluated: they yield `EvalError::Unsupported`. It is primarily a testing
+//! and witness-computation aid: it lets us run a `TypedFunction` without going
+//! through SNARK flattening, so expected outputs can be checked directly.
+
+use crate::typed_absy::types::Type;
+use crate::typed_absy::*;
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+/// A concrete value produced by evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<T> {
+    Field(T),
+    Boolean(bool),
+    Uint(u128),
+    Array(Vec<Value<T>>),
+}
+
+/// Reasons evaluation can fail at runtime.
+#[derive(Debug, PartialEq)]
+pub enum EvalError {
+    UnboundVariable(String),
+    OutOfBounds(usize),
+    DivisionByZero,
+    AssertionFailed,
+    /// A construct the evaluator does not implement (e.g. a function call that
+    /// has not been inlined).
+    Unsupported(&'static str),
+}
+
+/// Maps variables in scope to their current value.
+pub type Scope<T> = HashMap<String, Value<T>>;
+
+pub fn eval_field<'ast, T: Field>(
+    e: &FieldElementExpression<'ast, T>,
+    scope: &Scope<T>,
+) -> Result<T, EvalError> {
+    match e {
+        FieldElementExpression::Number(n) => Ok(n.clone()),
+        FieldElementExpression::Identifier(id) => match scope.get(&id.to_string()) {
+            Some(Value::Field(v)) => Ok(v.clone()),
+            _ => Err(EvalError::UnboundVariable(id.to_string())),
+        },
+        FieldElementExpression::Add(a, b) => Ok(eval_field(a, scope)? + eval_field(b, scope)?),
+        FieldElementExpression::Sub(a, b) => Ok(eval_field(a, scope)? - eval_field(b, scope)?),
+        FieldElementExpression::Mult(a, b) => Ok(eval_field(a, scope)? * eval_field(b, scope)?),
+        FieldElementExpression::Div(a, b) => {
+            let d = eval_field(b, scope)?;
+            if d == T::zero() {
+                Err(EvalError::DivisionByZero)
+            } else {
+                Ok(eval_field(a, scope)? / d)
+            }
+        }
+        FieldElementExpression::Pow(a, b) => {
+            let base = eval_field(a, scope)?;
+            let exp = eval_field(b, scope)?.to_dec_string().parse::<usize>().unwrap_or(0);
+            let mut acc = T::one();
+            for _ in 0..exp {
+                acc = acc * base.clone();
+            }
+            Ok(acc)
+        }
+        FieldElementExpression::IfElse(c, a, b) => {
+            if eval_bool(c, scope)? {
+                eval_field(a, scope)
+            } else {
+                eval_field(b, scope)
+            }
+        }
+        FieldElementExpression::Select(array, index) => {
+            let values = eval_array(array, scope)?;
+            let i = eval_field(index, scope)?
+                .to_dec_string()
+                .parse::<usize>()
+                .map_err(|_| EvalError::OutOfBounds(0))?;
+            match values.get(i) {
+                Some(Value::Field(v)) => Ok(v.clone()),
+                _ => Err(EvalError::OutOfBounds(i)),
+            }
+        }
+        FieldElementExpression::FunctionCall(..) => Err(EvalError::Unsupported("function call")),
+        FieldElementExpression::Member(..) => Err(EvalError::Unsupported("struct member")),
+        FieldElementExpression::Uint(..) => Err(EvalError::Unsupported("uint to field cast")),
+    }
+}
+
+pub fn eval_bool<'ast, T: Field>(
+    e: &BooleanExpression<'ast, T>,
+    scope: &Scope<T>,
+) -> Result<bool, EvalError> {
+    match e {
+        BooleanExpression::Value(v) => Ok(*v),
+        BooleanExpression::Identifier(id) => match scope.get(&id.to_string()) {
+            Some(Value::Boolean(v)) => Ok(*v),
+            _ => Err(EvalError::UnboundVariable(id.to_string())),
+        },
+        BooleanExpression::Lt(a, b) => Ok(eval_field(a, scope)? < eval_field(b, scope)?),
+        BooleanExpression::Le(a, b) => Ok(eval_field(a, scope)? <= eval_field(b, scope)?),
+        BooleanExpression::Gt(a, b) => Ok(eval_field(a, scope)? > eval_field(b, scope)?),
+        BooleanExpression::Ge(a, b) => Ok(eval_field(a, scope)? >= eval_field(b, scope)?),
+        BooleanExpression::FieldEq(a, b) => Ok(eval_field(a, scope)? == eval_field(b, scope)?),
+        BooleanExpression::BoolEq(a, b) => Ok(eval_bool(a, scope)? == eval_bool(b, scope)?),
+        BooleanExpression::Or(a, b) => Ok(eval_bool(a, scope)? || eval_bool(b, scope)?),
+        BooleanExpression::And(a, b) => Ok(eval_bool(a, scope)? && eval_bool(b, scope)?),
+        BooleanExpression::Not(a) => Ok(!eval_bool(a, scope)?),
+        BooleanExpression::IfElse(c, a, b) => {
+            if eval_bool(c, scope)? {
+                eval_bool(a, scope)
+            } else {
+                eval_bool(b, scope)
+            }
+        }
+        _ => Err(EvalError::Unsupported("boolean expression")),
+    }
+}
+
+fn eval_array<'ast, T: Field>(
+    e: &ArrayExpression<'ast, T>,
+    scope: &Scope<T>,
+) -> Result<Vec<Value<T>>, EvalError> {
+    match e.as_inner() {
+        ArrayExpressionInner::Value(values) => {
+            values.iter().map(|v| eval_expression(v, scope)).collect()
+        }
+        ArrayExpressionInner::Identifier(id) => match scope.get(&id.to_string()) {
+            Some(Value::Array(v)) => Ok(v.clone()),
+            _ => Err(EvalError::UnboundVariable(id.to_string())),
+        },
+        _ => Err(EvalError::Unsupported("array expression")),
+    }
+}
+
+fn eval_expression<'ast, T: Field>(
+    e: &TypedExpression<'ast, T>,
+    scope: &Scope<T>,
+) -> Result<Value<T>, EvalError> {
+    match e {
+        TypedExpression::FieldElement(e) => eval_field(e, scope).map(Value::Field),
+        TypedExpression::Boolean(e) => eval_bool(e, scope).map(Value::Boolean),
+        TypedExpression::Array(e) => eval_array(e, scope).map(Value::Array),
+        _ => Err(EvalError::Unsupported("expression")),
+    }
+}
+
+/// Evaluate the right-hand side of a definition into the scope under `name`.
+pub fn bind<'ast, T: Field>(
+    scope: &mut Scope<T>,
+    name: String,
+    e: &TypedExpression<'ast, T>,
+) -> Result<(), EvalError> {
+    let v = eval_expression(e, scope)?;
+    scope.insert(name, v);
+    Ok(())
+}
+
+/// The default value for a declared-but-unassigned variable of type `ty`.
+pub fn zero_value<T: Field>(ty: &Type) -> Value<T> {
+    match ty {
+        Type::Boolean => Value::Boolean(false),
+        Type::Uint(_) => Value::Uint(0),
+        Type::Array(array_type) => Value::Array(
+            (0..array_type.size).map(|_| zero_value(&array_type.ty)).collect(),
+        ),
+        _ => Value::Field(T::zero()),
+    }
+}
+
+/// Build a field element equal to `n` by adding `one` to `zero` `n` times, for binding a `For`
+/// loop index.
+fn field_from_usize<T: Field>(n: usize) -> T {
+    let mut v = T::zero();
+    for _ in 0..n {
+        v = v + T::one();
+    }
+    v
+}
+
+/// Interpreter state: the scope a statement runs against, plus the function/module registry
+/// needed to resolve a call. `scope` is swapped out (not merely shadowed) when entering a
+/// function body or loop iteration so a callee's or iteration's locals, including the loop
+/// index, can't leak into the caller -- the evaluator's equivalent of the checker's own
+/// `enter_scope`/`exit_scope` discipline in `semantics::Checker`.
+pub struct State<'ast, 'a, T: Field> {
+    scope: Scope<T>,
+    functions: &'a TypedFunctionSymbols<'ast, T>,
+    modules: &'a TypedModules<'ast, T>,
+}
+
+impl<'ast, 'a, T: Field> State<'ast, 'a, T> {
+    pub fn new(
+        functions: &'a TypedFunctionSymbols<'ast, T>,
+        modules: &'a TypedModules<'ast, T>,
+    ) -> Self {
+        State {
+            scope: Scope::new(),
+            functions,
+            modules,
+        }
+    }
+}
+
+/// Write `value` into `assignee`'s slot in `state`'s scope. `Select` covers an array-element
+/// write (see `semantics`'s `assign_to_select` test for the shape this mirrors); `Member` and
+/// `Slice` assignees aren't evaluated yet, matching the rest of this module's "tree walker for
+/// the subset the checker already exercises in its own tests" scope.
+fn assign<'ast, T: Field>(
+    state: &mut State<'ast, '_, T>,
+    assignee: &TypedAssignee<'ast, T>,
+    value: Value<T>,
+) -> Result<(), EvalError> {
+    match assignee {
+        TypedAssignee::Identifier(var) => {
+            state.scope.insert(var.id.to_string(), value);
+            Ok(())
+        }
+        TypedAssignee::Select(box inner, box index) => {
+            let name = match &**inner {
+                TypedAssignee::Identifier(var) => var.id.to_string(),
+                _ => return Err(EvalError::Unsupported("nested select assignee")),
+            };
+
+            let i = eval_field(index, &state.scope)?
+                .to_dec_string()
+                .parse::<usize>()
+                .map_err(|_| EvalError::OutOfBounds(0))?;
+
+            match state.scope.get_mut(&name) {
+                Some(Value::Array(values)) if i < values.len() => {
+                    values[i] = value;
+                    Ok(())
+                }
+                Some(Value::Array(values)) => Err(EvalError::OutOfBounds(values.len())),
+                _ => Err(EvalError::UnboundVariable(name)),
+            }
+        }
+        _ => Err(EvalError::Unsupported("member/slice assignee")),
+    }
+}
+
+/// Evaluate a single statement against `state`, returning `Some(values)` if it was a `Return`
+/// (the caller unwinds with these) or `None` to continue with the next statement.
+fn eval_statement<'ast, T: Field>(
+    state: &mut State<'ast, '_, T>,
+    stat: &TypedStatement<'ast, T>,
+) -> Result<Option<Vec<Value<T>>>, EvalError> {
+    match stat {
+        TypedStatement::Return(exprs) => {
+            let values = exprs
+                .iter()
+                .map(|e| eval_expression(e, &state.scope))
+                .collect::<Result<_, _>>()?;
+            Ok(Some(values))
+        }
+        TypedStatement::Declaration(var) => {
+            state.scope.insert(var.id.to_string(), zero_value(&var._type));
+            Ok(None)
+        }
+        TypedStatement::Definition(assignee, expr) => {
+            let value = eval_expression(expr, &state.scope)?;
+            assign(state, assignee, value)?;
+            Ok(None)
+        }
+        TypedStatement::MultipleDefinition(assignees, list) => {
+            let values = eval_expression_list(state, list)?;
+            for (assignee, value) in assignees.iter().zip(values.into_iter()) {
+                assign(state, assignee, value)?;
+            }
+            Ok(None)
+        }
+        TypedStatement::Assertion(e) => {
+            if eval_bool(e, &state.scope)? {
+                Ok(None)
+            } else {
+                Err(EvalError::AssertionFailed)
+            }
+        }
+        TypedStatement::For(var, from, to, statements) => {
+            let from = eval_field(from, &state.scope)?
+                .to_dec_string()
+                .parse::<usize>()
+                .map_err(|_| EvalError::OutOfBounds(0))?;
+            let to = eval_field(to, &state.scope)?
+                .to_dec_string()
+                .parse::<usize>()
+                .map_err(|_| EvalError::OutOfBounds(0))?;
+
+            for i in from..to {
+                let saved = state.scope.clone();
+                state
+                    .scope
+                    .insert(var.id.to_string(), Value::Field(field_from_usize(i)));
+
+                for s in statements {
+                    if let Some(values) = eval_statement(state, s)? {
+                        state.scope = saved;
+                        return Ok(Some(values));
+                    }
+                }
+
+                // drop the iteration's own locals and index, keep writes to outer variables
+                let mut restored = saved;
+                for (name, value) in restored.iter_mut() {
+                    if *name != var.id.to_string() {
+                        if let Some(current) = state.scope.get(name) {
+                            *value = current.clone();
+                        }
+                    }
+                }
+                state.scope = restored;
+            }
+            Ok(None)
+        }
+    }
+}
+
+fn eval_expression_list<'ast, T: Field>(
+    state: &mut State<'ast, '_, T>,
+    list: &TypedExpressionList<'ast, T>,
+) -> Result<Vec<Value<T>>, EvalError> {
+    match list {
+        TypedExpressionList::FunctionCall(key, arguments, _) => {
+            let arguments = arguments
+                .iter()
+                .map(|a| eval_expression(a, &state.scope))
+                .collect::<Result<_, _>>()?;
+            eval_call(state, key, arguments)
+        }
+    }
+}
+
+/// Look up `key` in `state`'s function registry, bind `arguments` to its parameters in a fresh
+/// scope, and run its body to a `Return`.
+fn eval_call<'ast, T: Field>(
+    state: &mut State<'ast, '_, T>,
+    key: &FunctionKey<'ast>,
+    arguments: Vec<Value<T>>,
+) -> Result<Vec<Value<T>>, EvalError> {
+    let symbol = state
+        .functions
+        .get(key)
+        .ok_or_else(|| EvalError::Unsupported("function not found"))?;
+
+    match symbol {
+        TypedFunctionSymbol::Here(function) => {
+            if function.arguments.len() != arguments.len() {
+                return Err(EvalError::Unsupported("argument count mismatch"));
+            }
+
+            let saved = std::mem::replace(&mut state.scope, Scope::new());
+
+            for (p, v) in function.arguments.iter().zip(arguments.into_iter()) {
+                state.scope.insert(p.id.id.to_string(), v);
+            }
+
+            let mut result = Ok(vec![]);
+            for s in &function.statements {
+                match eval_statement(state, s) {
+                    Ok(Some(values)) => {
+                        result = Ok(values);
+                        break;
+                    }
+                    Ok(None) => continue,
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+
+            state.scope = saved;
+            result
+        }
+        TypedFunctionSymbol::There(inner_key, module_id) => {
+            let functions = &state
+                .modules
+                .get(module_id)
+                .ok_or_else(|| EvalError::Unsupported("imported module not found"))?
+                .functions;
+
+            let mut nested = State {
+                scope: state.scope.clone(),
+                functions,
+                modules: state.modules,
+            };
+
+            eval_call(&mut nested, inner_key, arguments)
+        }
+        TypedFunctionSymbol::Flat(_) => Err(EvalError::Unsupported(
+            "flat embed has no typed body to evaluate",
+        )),
+    }
+}
+
+/// Runs a checked `TypedProgram` end to end against concrete inputs, without going through
+/// circuit flattening. A thin driver over `eval_call` that starts a fresh `State` from the
+/// main module's own function/module registry, the same one the checker already builds.
+pub struct Interpreter;
+
+impl Interpreter {
+    pub fn run<'ast, T: Field>(
+        program: &TypedProgram<'ast, T>,
+        entry: &FunctionKey<'ast>,
+        inputs: Vec<Value<T>>,
+    ) -> Result<Vec<Value<T>>, EvalError> {
+        let main_module = program
+            .modules
+            .get(&program.main)
+            .ok_or_else(|| EvalError::Unsupported("main module not found"))?;
+
+        let mut state = State::new(&main_module.functions, &program.modules);
+
+        eval_call(&mut state, entry, inputs)
+    }
+}
@@ -207,6 +207,25 @@ pub fn fold_array_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
             let index = f.fold_field_expression(index);
             ArrayExpressionInner::Select(box array, box index)
         }
+        ArrayExpressionInner::Repeat(box e, count) => {
+            ArrayExpressionInner::Repeat(box f.fold_expression(e), count)
+        }
+        ArrayExpressionInner::Spread(elements) => ArrayExpressionInner::Spread(
+            elements
+                .into_iter()
+                .map(|e| match e {
+                    ArraySpreadElement::Element(e) => ArraySpreadElement::Element(f.fold_expression(e)),
+                    ArraySpreadElement::Spread(a) => {
+                        ArraySpreadElement::Spread(f.fold_array_expression(a))
+                    }
+                })
+                .collect(),
+        ),
+        ArrayExpressionInner::Slice(box array, box from, box to) => ArrayExpressionInner::Slice(
+            box f.fold_array_expression(array),
+            box f.fold_field_expression(from),
+            box f.fold_field_expression(to),
+        ),
     }
 }
 
@@ -291,6 +310,9 @@ pub fn fold_field_expression<'ast, T: Field, F: Folder<'ast, T>>(
             let s = f.fold_struct_expression(s);
             FieldElementExpression::Member(box s, id)
         }
+        FieldElementExpression::Uint(box e) => {
+            FieldElementExpression::Uint(box f.fold_uint_expression(e))
+        }
         FieldElementExpression::Select(box array, box index) => {
             let array = f.fold_array_expression(array);
             let index = f.fold_field_expression(index);
@@ -551,6 +573,11 @@ pub fn fold_assignee<'ast, T: Field, F: Folder<'ast, T>>(
             TypedAssignee::Select(box f.fold_assignee(a), box f.fold_field_expression(index))
         }
         TypedAssignee::Member(box s, m) => TypedAssignee::Member(box f.fold_assignee(s), m),
+        TypedAssignee::Slice(box a, box lo, box hi) => TypedAssignee::Slice(
+            box f.fold_assignee(a),
+            box f.fold_field_expression(lo),
+            box f.fold_field_expression(hi),
+        ),
     }
 }
 
@@ -567,3 +594,662 @@ pub fn fold_program<'ast, T: Field, F: Folder<'ast, T>>(
         main: p.main,
     }
 }
+
+// A fallible counterpart to `Folder`, for passes that need to reject a program instead of
+// panicking or smuggling errors through side state. Mirrors `Folder`'s structure method for
+// method, but every `fold_*` returns a `Result` and uses `?` to short-circuit on the first error.
+pub trait ResultFolder<'ast, T: Field>: Sized {
+    type Error;
+
+    fn fold_program(
+        &mut self,
+        p: TypedProgram<'ast, T>,
+    ) -> Result<TypedProgram<'ast, T>, Self::Error> {
+        fold_program(self, p)
+    }
+
+    fn fold_module(
+        &mut self,
+        p: TypedModule<'ast, T>,
+    ) -> Result<TypedModule<'ast, T>, Self::Error> {
+        fold_module(self, p)
+    }
+
+    fn fold_function_symbol(
+        &mut self,
+        s: TypedFunctionSymbol<'ast, T>,
+    ) -> Result<TypedFunctionSymbol<'ast, T>, Self::Error> {
+        fold_function_symbol(self, s)
+    }
+
+    fn fold_function(
+        &mut self,
+        f: TypedFunction<'ast, T>,
+    ) -> Result<TypedFunction<'ast, T>, Self::Error> {
+        fold_function(self, f)
+    }
+
+    fn fold_parameter(&mut self, p: Parameter<'ast>) -> Result<Parameter<'ast>, Self::Error> {
+        Ok(Parameter {
+            id: self.fold_variable(p.id)?,
+            ..p
+        })
+    }
+
+    fn fold_name(&mut self, n: Identifier<'ast>) -> Result<Identifier<'ast>, Self::Error> {
+        Ok(n)
+    }
+
+    fn fold_variable(&mut self, v: Variable<'ast>) -> Result<Variable<'ast>, Self::Error> {
+        Ok(Variable {
+            id: self.fold_name(v.id)?,
+            ..v
+        })
+    }
+
+    fn fold_assignee(
+        &mut self,
+        a: TypedAssignee<'ast, T>,
+    ) -> Result<TypedAssignee<'ast, T>, Self::Error> {
+        fold_assignee(self, a)
+    }
+
+    fn fold_statement(
+        &mut self,
+        s: TypedStatement<'ast, T>,
+    ) -> Result<Vec<TypedStatement<'ast, T>>, Self::Error> {
+        fold_statement(self, s)
+    }
+
+    fn fold_expression(
+        &mut self,
+        e: TypedExpression<'ast, T>,
+    ) -> Result<TypedExpression<'ast, T>, Self::Error> {
+        Ok(match e {
+            TypedExpression::FieldElement(e) => self.fold_field_expression(e)?.into(),
+            TypedExpression::Boolean(e) => self.fold_boolean_expression(e)?.into(),
+            TypedExpression::Uint(e) => self.fold_uint_expression(e)?.into(),
+            TypedExpression::Array(e) => self.fold_array_expression(e)?.into(),
+            TypedExpression::Struct(e) => self.fold_struct_expression(e)?.into(),
+        })
+    }
+
+    fn fold_array_expression(
+        &mut self,
+        e: ArrayExpression<'ast, T>,
+    ) -> Result<ArrayExpression<'ast, T>, Self::Error> {
+        fold_array_expression(self, e)
+    }
+
+    fn fold_struct_expression(
+        &mut self,
+        e: StructExpression<'ast, T>,
+    ) -> Result<StructExpression<'ast, T>, Self::Error> {
+        fold_struct_expression(self, e)
+    }
+
+    fn fold_expression_list(
+        &mut self,
+        es: TypedExpressionList<'ast, T>,
+    ) -> Result<TypedExpressionList<'ast, T>, Self::Error> {
+        match es {
+            TypedExpressionList::FunctionCall(id, arguments, types) => {
+                Ok(TypedExpressionList::FunctionCall(
+                    id,
+                    arguments
+                        .into_iter()
+                        .map(|a| self.fold_expression(a))
+                        .collect::<Result<_, _>>()?,
+                    types,
+                ))
+            }
+        }
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> Result<FieldElementExpression<'ast, T>, Self::Error> {
+        fold_field_expression(self, e)
+    }
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> Result<BooleanExpression<'ast, T>, Self::Error> {
+        fold_boolean_expression(self, e)
+    }
+    fn fold_uint_expression(
+        &mut self,
+        e: UExpression<'ast, T>,
+    ) -> Result<UExpression<'ast, T>, Self::Error> {
+        fold_uint_expression(self, e)
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> Result<UExpressionInner<'ast, T>, Self::Error> {
+        fold_uint_expression_inner(self, bitwidth, e)
+    }
+
+    fn fold_array_expression_inner(
+        &mut self,
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> Result<ArrayExpressionInner<'ast, T>, Self::Error> {
+        fold_array_expression_inner(self, ty, size, e)
+    }
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> Result<StructExpressionInner<'ast, T>, Self::Error> {
+        fold_struct_expression_inner(self, ty, e)
+    }
+}
+
+pub fn fold_module<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    p: TypedModule<'ast, T>,
+) -> Result<TypedModule<'ast, T>, F::Error> {
+    Ok(TypedModule {
+        functions: p
+            .functions
+            .into_iter()
+            .map(|(key, fun)| Ok((key, f.fold_function_symbol(fun)?)))
+            .collect::<Result<_, F::Error>>()?,
+        ..p
+    })
+}
+
+pub fn fold_statement<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    s: TypedStatement<'ast, T>,
+) -> Result<Vec<TypedStatement<'ast, T>>, F::Error> {
+    let res = match s {
+        TypedStatement::Return(expressions) => TypedStatement::Return(
+            expressions
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?,
+        ),
+        TypedStatement::Definition(a, e) => {
+            TypedStatement::Definition(f.fold_assignee(a)?, f.fold_expression(e)?)
+        }
+        TypedStatement::Declaration(v) => TypedStatement::Declaration(f.fold_variable(v)?),
+        TypedStatement::Assertion(e) => TypedStatement::Assertion(f.fold_boolean_expression(e)?),
+        TypedStatement::For(v, from, to, statements) => TypedStatement::For(
+            f.fold_variable(v)?,
+            from,
+            to,
+            statements
+                .into_iter()
+                .map(|s| f.fold_statement(s))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect(),
+        ),
+        TypedStatement::MultipleDefinition(assignees, elist) => TypedStatement::MultipleDefinition(
+            assignees
+                .into_iter()
+                .map(|a| f.fold_assignee(a))
+                .collect::<Result<_, _>>()?,
+            f.fold_expression_list(elist)?,
+        ),
+    };
+    Ok(vec![res])
+}
+
+pub fn fold_array_expression_inner<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    _: &Type,
+    _: usize,
+    e: ArrayExpressionInner<'ast, T>,
+) -> Result<ArrayExpressionInner<'ast, T>, F::Error> {
+    Ok(match e {
+        ArrayExpressionInner::Identifier(id) => {
+            ArrayExpressionInner::Identifier(f.fold_name(id)?)
+        }
+        ArrayExpressionInner::Value(exprs) => ArrayExpressionInner::Value(
+            exprs
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?,
+        ),
+        ArrayExpressionInner::FunctionCall(id, exps) => {
+            let exps = exps
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?;
+            ArrayExpressionInner::FunctionCall(id, exps)
+        }
+        ArrayExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+            ArrayExpressionInner::IfElse(
+                box f.fold_boolean_expression(condition)?,
+                box f.fold_array_expression(consequence)?,
+                box f.fold_array_expression(alternative)?,
+            )
+        }
+        ArrayExpressionInner::Member(box s, id) => {
+            let s = f.fold_struct_expression(s)?;
+            ArrayExpressionInner::Member(box s, id)
+        }
+        ArrayExpressionInner::Select(box array, box index) => {
+            let array = f.fold_array_expression(array)?;
+            let index = f.fold_field_expression(index)?;
+            ArrayExpressionInner::Select(box array, box index)
+        }
+        ArrayExpressionInner::Repeat(box e, count) => {
+            ArrayExpressionInner::Repeat(box f.fold_expression(e)?, count)
+        }
+        ArrayExpressionInner::Spread(elements) => ArrayExpressionInner::Spread(
+            elements
+                .into_iter()
+                .map(|e| {
+                    Ok(match e {
+                        ArraySpreadElement::Element(e) => {
+                            ArraySpreadElement::Element(f.fold_expression(e)?)
+                        }
+                        ArraySpreadElement::Spread(a) => {
+                            ArraySpreadElement::Spread(f.fold_array_expression(a)?)
+                        }
+                    })
+                })
+                .collect::<Result<_, F::Error>>()?,
+        ),
+        ArrayExpressionInner::Slice(box array, box from, box to) => ArrayExpressionInner::Slice(
+            box f.fold_array_expression(array)?,
+            box f.fold_field_expression(from)?,
+            box f.fold_field_expression(to)?,
+        ),
+    })
+}
+
+pub fn fold_struct_expression_inner<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    _: &StructType,
+    e: StructExpressionInner<'ast, T>,
+) -> Result<StructExpressionInner<'ast, T>, F::Error> {
+    Ok(match e {
+        StructExpressionInner::Identifier(id) => {
+            StructExpressionInner::Identifier(f.fold_name(id)?)
+        }
+        StructExpressionInner::Value(exprs) => StructExpressionInner::Value(
+            exprs
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?,
+        ),
+        StructExpressionInner::FunctionCall(id, exps) => {
+            let exps = exps
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?;
+            StructExpressionInner::FunctionCall(id, exps)
+        }
+        StructExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+            StructExpressionInner::IfElse(
+                box f.fold_boolean_expression(condition)?,
+                box f.fold_struct_expression(consequence)?,
+                box f.fold_struct_expression(alternative)?,
+            )
+        }
+        StructExpressionInner::Member(box s, id) => {
+            let s = f.fold_struct_expression(s)?;
+            StructExpressionInner::Member(box s, id)
+        }
+        StructExpressionInner::Select(box array, box index) => {
+            let array = f.fold_array_expression(array)?;
+            let index = f.fold_field_expression(index)?;
+            StructExpressionInner::Select(box array, box index)
+        }
+    })
+}
+
+pub fn fold_field_expression<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    e: FieldElementExpression<'ast, T>,
+) -> Result<FieldElementExpression<'ast, T>, F::Error> {
+    Ok(match e {
+        FieldElementExpression::Number(n) => FieldElementExpression::Number(n),
+        FieldElementExpression::Identifier(id) => {
+            FieldElementExpression::Identifier(f.fold_name(id)?)
+        }
+        FieldElementExpression::Add(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            FieldElementExpression::Add(box e1, box e2)
+        }
+        FieldElementExpression::Sub(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            FieldElementExpression::Sub(box e1, box e2)
+        }
+        FieldElementExpression::Mult(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            FieldElementExpression::Mult(box e1, box e2)
+        }
+        FieldElementExpression::Div(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            FieldElementExpression::Div(box e1, box e2)
+        }
+        FieldElementExpression::Pow(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            FieldElementExpression::Pow(box e1, box e2)
+        }
+        FieldElementExpression::IfElse(box cond, box cons, box alt) => {
+            let cond = f.fold_boolean_expression(cond)?;
+            let cons = f.fold_field_expression(cons)?;
+            let alt = f.fold_field_expression(alt)?;
+            FieldElementExpression::IfElse(box cond, box cons, box alt)
+        }
+        FieldElementExpression::FunctionCall(key, exps) => {
+            let exps = exps
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?;
+            FieldElementExpression::FunctionCall(key, exps)
+        }
+        FieldElementExpression::Member(box s, id) => {
+            let s = f.fold_struct_expression(s)?;
+            FieldElementExpression::Member(box s, id)
+        }
+        FieldElementExpression::Uint(box e) => {
+            FieldElementExpression::Uint(box f.fold_uint_expression(e)?)
+        }
+        FieldElementExpression::Select(box array, box index) => {
+            let array = f.fold_array_expression(array)?;
+            let index = f.fold_field_expression(index)?;
+            FieldElementExpression::Select(box array, box index)
+        }
+    })
+}
+
+pub fn fold_boolean_expression<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    e: BooleanExpression<'ast, T>,
+) -> Result<BooleanExpression<'ast, T>, F::Error> {
+    Ok(match e {
+        BooleanExpression::Value(v) => BooleanExpression::Value(v),
+        BooleanExpression::Identifier(id) => BooleanExpression::Identifier(f.fold_name(id)?),
+        BooleanExpression::FieldEq(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            BooleanExpression::FieldEq(box e1, box e2)
+        }
+        BooleanExpression::BoolEq(box e1, box e2) => {
+            let e1 = f.fold_boolean_expression(e1)?;
+            let e2 = f.fold_boolean_expression(e2)?;
+            BooleanExpression::BoolEq(box e1, box e2)
+        }
+        BooleanExpression::ArrayEq(box e1, box e2) => {
+            let e1 = f.fold_array_expression(e1)?;
+            let e2 = f.fold_array_expression(e2)?;
+            BooleanExpression::ArrayEq(box e1, box e2)
+        }
+        BooleanExpression::StructEq(box e1, box e2) => {
+            let e1 = f.fold_struct_expression(e1)?;
+            let e2 = f.fold_struct_expression(e2)?;
+            BooleanExpression::StructEq(box e1, box e2)
+        }
+        BooleanExpression::UintEq(box e1, box e2) => {
+            let e1 = f.fold_uint_expression(e1)?;
+            let e2 = f.fold_uint_expression(e2)?;
+            BooleanExpression::UintEq(box e1, box e2)
+        }
+        BooleanExpression::Lt(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            BooleanExpression::Lt(box e1, box e2)
+        }
+        BooleanExpression::Le(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            BooleanExpression::Le(box e1, box e2)
+        }
+        BooleanExpression::Gt(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            BooleanExpression::Gt(box e1, box e2)
+        }
+        BooleanExpression::Ge(box e1, box e2) => {
+            let e1 = f.fold_field_expression(e1)?;
+            let e2 = f.fold_field_expression(e2)?;
+            BooleanExpression::Ge(box e1, box e2)
+        }
+        BooleanExpression::Or(box e1, box e2) => {
+            let e1 = f.fold_boolean_expression(e1)?;
+            let e2 = f.fold_boolean_expression(e2)?;
+            BooleanExpression::Or(box e1, box e2)
+        }
+        BooleanExpression::And(box e1, box e2) => {
+            let e1 = f.fold_boolean_expression(e1)?;
+            let e2 = f.fold_boolean_expression(e2)?;
+            BooleanExpression::And(box e1, box e2)
+        }
+        BooleanExpression::Not(box e) => {
+            let e = f.fold_boolean_expression(e)?;
+            BooleanExpression::Not(box e)
+        }
+        BooleanExpression::FunctionCall(key, exps) => {
+            let exps = exps
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?;
+            BooleanExpression::FunctionCall(key, exps)
+        }
+        BooleanExpression::IfElse(box cond, box cons, box alt) => {
+            let cond = f.fold_boolean_expression(cond)?;
+            let cons = f.fold_boolean_expression(cons)?;
+            let alt = f.fold_boolean_expression(alt)?;
+            BooleanExpression::IfElse(box cond, box cons, box alt)
+        }
+        BooleanExpression::Member(box s, id) => {
+            let s = f.fold_struct_expression(s)?;
+            BooleanExpression::Member(box s, id)
+        }
+        BooleanExpression::Select(box array, box index) => {
+            let array = f.fold_array_expression(array)?;
+            let index = f.fold_field_expression(index)?;
+            BooleanExpression::Select(box array, box index)
+        }
+    })
+}
+
+pub fn fold_uint_expression<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    e: UExpression<'ast, T>,
+) -> Result<UExpression<'ast, T>, F::Error> {
+    Ok(UExpression {
+        inner: f.fold_uint_expression_inner(e.bitwidth, e.inner)?,
+        ..e
+    })
+}
+
+pub fn fold_uint_expression_inner<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    _: UBitwidth,
+    e: UExpressionInner<'ast, T>,
+) -> Result<UExpressionInner<'ast, T>, F::Error> {
+    Ok(match e {
+        UExpressionInner::Value(v) => UExpressionInner::Value(v),
+        UExpressionInner::Identifier(id) => UExpressionInner::Identifier(f.fold_name(id)?),
+        UExpressionInner::Add(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::Add(box left, box right)
+        }
+        UExpressionInner::Sub(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::Sub(box left, box right)
+        }
+        UExpressionInner::Mult(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::Mult(box left, box right)
+        }
+        UExpressionInner::Div(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::Div(box left, box right)
+        }
+        UExpressionInner::Rem(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::Rem(box left, box right)
+        }
+        UExpressionInner::Xor(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::Xor(box left, box right)
+        }
+        UExpressionInner::And(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::And(box left, box right)
+        }
+        UExpressionInner::Or(box left, box right) => {
+            let left = f.fold_uint_expression(left)?;
+            let right = f.fold_uint_expression(right)?;
+
+            UExpressionInner::Or(box left, box right)
+        }
+        UExpressionInner::LeftShift(box e, box by) => {
+            let e = f.fold_uint_expression(e)?;
+            let by = f.fold_field_expression(by)?;
+
+            UExpressionInner::LeftShift(box e, box by)
+        }
+        UExpressionInner::RightShift(box e, box by) => {
+            let e = f.fold_uint_expression(e)?;
+            let by = f.fold_field_expression(by)?;
+
+            UExpressionInner::RightShift(box e, box by)
+        }
+        UExpressionInner::Not(box e) => {
+            let e = f.fold_uint_expression(e)?;
+
+            UExpressionInner::Not(box e)
+        }
+        UExpressionInner::FunctionCall(key, exps) => {
+            let exps = exps
+                .into_iter()
+                .map(|e| f.fold_expression(e))
+                .collect::<Result<_, _>>()?;
+            UExpressionInner::FunctionCall(key, exps)
+        }
+        UExpressionInner::Select(box array, box index) => {
+            let array = f.fold_array_expression(array)?;
+            let index = f.fold_field_expression(index)?;
+            UExpressionInner::Select(box array, box index)
+        }
+        UExpressionInner::IfElse(box cond, box cons, box alt) => {
+            let cond = f.fold_boolean_expression(cond)?;
+            let cons = f.fold_uint_expression(cons)?;
+            let alt = f.fold_uint_expression(alt)?;
+            UExpressionInner::IfElse(box cond, box cons, box alt)
+        }
+        UExpressionInner::Member(box s, id) => {
+            let s = f.fold_struct_expression(s)?;
+            UExpressionInner::Member(box s, id)
+        }
+    })
+}
+
+pub fn fold_function<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    fun: TypedFunction<'ast, T>,
+) -> Result<TypedFunction<'ast, T>, F::Error> {
+    Ok(TypedFunction {
+        arguments: fun
+            .arguments
+            .into_iter()
+            .map(|a| f.fold_parameter(a))
+            .collect::<Result<_, _>>()?,
+        statements: fun
+            .statements
+            .into_iter()
+            .map(|s| f.fold_statement(s))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect(),
+        ..fun
+    })
+}
+
+pub fn fold_array_expression<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    e: ArrayExpression<'ast, T>,
+) -> Result<ArrayExpression<'ast, T>, F::Error> {
+    Ok(ArrayExpression {
+        inner: f.fold_array_expression_inner(&e.ty, e.size, e.inner)?,
+        ..e
+    })
+}
+
+pub fn fold_struct_expression<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    e: StructExpression<'ast, T>,
+) -> Result<StructExpression<'ast, T>, F::Error> {
+    Ok(StructExpression {
+        inner: f.fold_struct_expression_inner(&e.ty, e.inner)?,
+        ..e
+    })
+}
+
+pub fn fold_function_symbol<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    s: TypedFunctionSymbol<'ast, T>,
+) -> Result<TypedFunctionSymbol<'ast, T>, F::Error> {
+    Ok(match s {
+        TypedFunctionSymbol::Here(fun) => TypedFunctionSymbol::Here(f.fold_function(fun)?),
+        there => there, // by default, do not fold modules recursively
+    })
+}
+
+pub fn fold_assignee<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    a: TypedAssignee<'ast, T>,
+) -> Result<TypedAssignee<'ast, T>, F::Error> {
+    Ok(match a {
+        TypedAssignee::Identifier(v) => TypedAssignee::Identifier(f.fold_variable(v)?),
+        TypedAssignee::Select(box a, box index) => {
+            TypedAssignee::Select(box f.fold_assignee(a)?, box f.fold_field_expression(index)?)
+        }
+        TypedAssignee::Member(box s, m) => TypedAssignee::Member(box f.fold_assignee(s)?, m),
+        TypedAssignee::Slice(box a, box lo, box hi) => TypedAssignee::Slice(
+            box f.fold_assignee(a)?,
+            box f.fold_field_expression(lo)?,
+            box f.fold_field_expression(hi)?,
+        ),
+    })
+}
+
+pub fn fold_program<'ast, T: Field, F: ResultFolder<'ast, T>>(
+    f: &mut F,
+    p: TypedProgram<'ast, T>,
+) -> Result<TypedProgram<'ast, T>, F::Error> {
+    Ok(TypedProgram {
+        modules: p
+            .modules
+            .into_iter()
+            .map(|(module_id, module)| Ok((module_id, f.fold_module(module)?)))
+            .collect::<Result<_, F::Error>>()?,
+        main: p.main,
+    })
+}
@@ -27,6 +27,9 @@ pub struct Identifier<'ast> {
     /// the version of the variable, used after SSA transformation
     pub version: usize,
     /// the call stack of the variable, used when inlining
+    //
+    // A call-stack-aware `Inliner` pass is deferred; see `zokrates_core/DEFERRED.md`
+    // (chunk13-5) for why and the concrete path once unblocked.
     pub stack: Vec<(TypedModuleId, FunctionKeyHash, usize)>,
 }
 
@@ -0,0 +1,114 @@
+//! Hindley-Milner style type inference over the typed AST.
+//!
+//! This module provides a type term that can contain unification variables, a
+//! substitution, and a `unify` routine. The semantic checker uses `unify` only
+//! to compare the two operand types of a bitwise operator, both always known;
+//! it does not assign fresh variables to declarations.
+
+use crate::typed_absy::types::Type;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A type possibly containing unification variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InferType {
+    /// A fully known type.
+    Known(Type),
+    /// An unresolved unification variable.
+    Var(usize),
+    /// An array whose element type may still be a variable.
+    Array(Box<InferType>, usize),
+}
+
+impl From<Type> for InferType {
+    fn from(t: Type) -> InferType {
+        InferType::Known(t)
+    }
+}
+
+impl fmt::Display for InferType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InferType::Known(t) => write!(f, "{}", t),
+            InferType::Var(i) => write!(f, "?{}", i),
+            InferType::Array(t, n) => write!(f, "{}[{}]", t, n),
+        }
+    }
+}
+
+/// A mismatch discovered during unification.
+#[derive(Debug, PartialEq)]
+pub struct UnifyError {
+    pub expected: InferType,
+    pub got: InferType,
+}
+
+/// The inference context: a supply of fresh variables and the substitution
+/// accumulated so far.
+#[derive(Default)]
+pub struct Inferrer {
+    next: usize,
+    subst: HashMap<usize, InferType>,
+}
+
+impl Inferrer {
+    pub fn new() -> Self {
+        Inferrer::default()
+    }
+
+    /// Allocate a fresh, unconstrained type variable.
+    pub fn fresh(&mut self) -> InferType {
+        let v = self.next;
+        self.next += 1;
+        InferType::Var(v)
+    }
+
+    /// Apply the current substitution to `t`, following variable chains.
+    pub fn resolve(&self, t: &InferType) -> InferType {
+        match t {
+            InferType::Var(v) => match self.subst.get(v) {
+                Some(bound) => self.resolve(bound),
+                None => t.clone(),
+            },
+            InferType::Array(inner, n) => {
+                InferType::Array(Box::new(self.resolve(inner)), *n)
+            }
+            InferType::Known(_) => t.clone(),
+        }
+    }
+
+    /// Unify two type terms, extending the substitution. Returns `Err` on a
+    /// structural mismatch between two known types.
+    pub fn unify(&mut self, a: &InferType, b: &InferType) -> Result<(), UnifyError> {
+        let a = self.resolve(a);
+        let b = self.resolve(b);
+        match (a, b) {
+            (InferType::Var(v), InferType::Var(w)) if v == w => Ok(()),
+            (InferType::Var(v), other) | (other, InferType::Var(v)) => {
+                self.subst.insert(v, other);
+                Ok(())
+            }
+            (InferType::Known(x), InferType::Known(y)) => {
+                if x == y {
+                    Ok(())
+                } else {
+                    Err(UnifyError {
+                        expected: InferType::Known(x),
+                        got: InferType::Known(y),
+                    })
+                }
+            }
+            (InferType::Array(x, n), InferType::Array(y, m)) if n == m => self.unify(&x, &y),
+            (expected, got) => Err(UnifyError { expected, got }),
+        }
+    }
+
+    /// Resolve `t` to a concrete `Type`, failing if any variable is still
+    /// unconstrained (i.e. inference could not determine the type).
+    pub fn concretize(&self, t: &InferType) -> Option<Type> {
+        match self.resolve(t) {
+            InferType::Known(t) => Some(t),
+            _ => None,
+        }
+    }
+}
@@ -8,6 +8,14 @@
 pub mod abi;
 pub mod folder;
 pub mod identifier;
+pub mod evaluator;
+pub mod infer;
+pub mod span;
+pub mod size;
+pub mod propagator;
+pub mod rewrite;
+pub mod ssa;
+pub mod visitor;
 
 mod parameter;
 pub mod types;
@@ -257,6 +265,11 @@ pub enum TypedAssignee<'ast, T> {
         Box<FieldElementExpression<'ast, T>>,
     ),
     Member(Box<TypedAssignee<'ast, T>>, MemberId),
+    Slice(
+        Box<TypedAssignee<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
+    ),
 }
 
 impl<'ast, T> From<Variable<'ast>> for TypedAssignee<'ast, T> {
@@ -288,6 +301,28 @@ impl<'ast, T> Typed for TypedAssignee<'ast, T> {
                     _ => unreachable!("a struct access should only be defined over structs"),
                 }
             }
+            TypedAssignee::Slice(ref a, ref lo, ref hi) => {
+                let a_type = a.get_type();
+                match a_type {
+                    Type::Array(t) => {
+                        // the slice length is `hi - lo` when both bounds are compile-time
+                        // constants; otherwise the whole array's length is reported
+                        let size = match (lo.as_ref(), hi.as_ref()) {
+                            (
+                                FieldElementExpression::Number(lo),
+                                FieldElementExpression::Number(hi),
+                            ) => hi
+                                .to_dec_string()
+                                .parse::<usize>()
+                                .unwrap()
+                                .saturating_sub(lo.to_dec_string().parse::<usize>().unwrap()),
+                            _ => t.size,
+                        };
+                        Type::Array(ArrayType::new(*t.ty, size))
+                    }
+                    _ => unreachable!("a slice assignee should only be defined over arrays"),
+                }
+            }
         }
     }
 }
@@ -298,6 +333,9 @@ impl<'ast, T: fmt::Debug> fmt::Debug for TypedAssignee<'ast, T> {
             TypedAssignee::Identifier(ref s) => write!(f, "{}", s.id),
             TypedAssignee::Select(ref a, ref e) => write!(f, "Select({:?}, {:?})", a, e),
             TypedAssignee::Member(ref s, ref m) => write!(f, "Member({:?}, {:?})", s, m),
+            TypedAssignee::Slice(ref a, ref lo, ref hi) => {
+                write!(f, "Slice({:?}, {:?}, {:?})", a, lo, hi)
+            }
         }
     }
 }
@@ -308,6 +346,7 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedAssignee<'ast, T> {
             TypedAssignee::Identifier(ref s) => write!(f, "{}", s.id),
             TypedAssignee::Select(ref a, ref e) => write!(f, "{}[{}]", a, e),
             TypedAssignee::Member(ref s, ref m) => write!(f, "{}.{}", s, m),
+            TypedAssignee::Slice(ref a, ref lo, ref hi) => write!(f, "{}[{}..{}]", a, lo, hi),
         }
     }
 }
@@ -499,7 +538,8 @@ impl<'ast, T: fmt::Display> fmt::Display for StructExpression<'ast, T> {
             StructExpressionInner::Identifier(ref var) => write!(f, "{}", var),
             StructExpressionInner::Value(ref values) => write!(
                 f,
-                "{{{}}}",
+                "{} {{{}}}",
+                self.ty,
                 self.ty
                     .iter()
                     .map(|member| member.id.clone())
@@ -632,6 +672,9 @@ pub enum FieldElementExpression<'ast, T> {
         Box<ArrayExpression<'ast, T>>,
         Box<FieldElementExpression<'ast, T>>,
     ),
+    /// An explicit, lossless widening of a `Uint` to a `field`, inserted by `coerce`
+    /// so that this conversion always shows up as a dedicated node in the typed AST.
+    Uint(Box<UExpression<'ast, T>>),
 }
 
 impl<'ast, T> From<T> for FieldElementExpression<'ast, T> {
@@ -724,6 +767,26 @@ pub enum ArrayExpressionInner<'ast, T> {
         Box<ArrayExpression<'ast, T>>,
         Box<FieldElementExpression<'ast, T>>,
     ),
+    /// `[value; count]`: `count` copies of a single element.
+    Repeat(Box<TypedExpression<'ast, T>>, usize),
+    /// Concatenation of array and single-element fragments, as produced by
+    /// spread literals such as `[...a, b, ...c]`.
+    Spread(Vec<ArraySpreadElement<'ast, T>>),
+    /// `a[from..to]`: the contiguous sub-array from index `from` (inclusive) to
+    /// `to` (exclusive).
+    Slice(
+        Box<ArrayExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
+    ),
+}
+
+/// A fragment of a spread array literal: either a single element or an array
+/// whose elements are spliced in.
+#[derive(Clone, PartialEq, Hash, Eq, Debug)]
+pub enum ArraySpreadElement<'ast, T> {
+    Element(TypedExpression<'ast, T>),
+    Spread(ArrayExpression<'ast, T>),
 }
 
 impl<'ast, T> ArrayExpressionInner<'ast, T> {
@@ -895,6 +958,7 @@ impl<'ast, T: fmt::Display> fmt::Display for FieldElementExpression<'ast, T> {
             }
             FieldElementExpression::Member(ref struc, ref id) => write!(f, "{}.{}", struc, id),
             FieldElementExpression::Select(ref id, ref index) => write!(f, "{}[{}]", id, index),
+            FieldElementExpression::Uint(ref e) => write!(f, "{}", e),
         }
     }
 }
@@ -1004,6 +1068,22 @@ impl<'ast, T: fmt::Display> fmt::Display for ArrayExpressionInner<'ast, T> {
             ),
             ArrayExpressionInner::Member(ref s, ref id) => write!(f, "{}.{}", s, id),
             ArrayExpressionInner::Select(ref id, ref index) => write!(f, "{}[{}]", id, index),
+            ArrayExpressionInner::Repeat(ref e, ref count) => write!(f, "[{}; {}]", e, count),
+            ArrayExpressionInner::Spread(ref elements) => write!(
+                f,
+                "[{}]",
+                elements
+                    .iter()
+                    .map(|e| match e {
+                        ArraySpreadElement::Element(e) => e.to_string(),
+                        ArraySpreadElement::Spread(a) => format!("...{}", a),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ArrayExpressionInner::Slice(ref a, ref from, ref to) => {
+                write!(f, "{}[{}..{}]", a, from, to)
+            }
         }
     }
 }
@@ -1085,6 +1165,7 @@ impl<'ast, T: fmt::Debug> fmt::Debug for FieldElementExpression<'ast, T> {
             FieldElementExpression::Select(ref id, ref index) => {
                 write!(f, "Select({:?}, {:?})", id, index)
             }
+            FieldElementExpression::Uint(ref e) => write!(f, "Uint({:?})", e),
         }
     }
 }
@@ -1110,6 +1191,15 @@ impl<'ast, T: fmt::Debug> fmt::Debug for ArrayExpressionInner<'ast, T> {
             ArrayExpressionInner::Select(ref id, ref index) => {
                 write!(f, "Select({:?}, {:?})", id, index)
             }
+            ArrayExpressionInner::Repeat(ref e, ref count) => {
+                write!(f, "Repeat({:?}, {:?})", e, count)
+            }
+            ArrayExpressionInner::Spread(ref elements) => {
+                write!(f, "Spread({:?})", elements)
+            }
+            ArrayExpressionInner::Slice(ref a, ref from, ref to) => {
+                write!(f, "Slice({:?}, {:?}, {:?})", a, from, to)
+            }
         }
     }
 }
@@ -1237,6 +1327,34 @@ pub trait Select<'ast, T> {
     fn select(array: ArrayExpression<'ast, T>, index: FieldElementExpression<'ast, T>) -> Self;
 }
 
+pub trait Slice<'ast, T> {
+    fn slice(
+        array: ArrayExpression<'ast, T>,
+        from: FieldElementExpression<'ast, T>,
+        to: FieldElementExpression<'ast, T>,
+    ) -> Self;
+}
+
+impl<'ast, T: Field> Slice<'ast, T> for ArrayExpression<'ast, T> {
+    fn slice(
+        array: ArrayExpression<'ast, T>,
+        from: FieldElementExpression<'ast, T>,
+        to: FieldElementExpression<'ast, T>,
+    ) -> Self {
+        let ty = array.inner_type().clone();
+        // the same length a slice assignee's `get_type` reports
+        let size = match (&from, &to) {
+            (FieldElementExpression::Number(from), FieldElementExpression::Number(to)) => to
+                .to_dec_string()
+                .parse::<usize>()
+                .unwrap()
+                .saturating_sub(from.to_dec_string().parse::<usize>().unwrap()),
+            _ => array.size(),
+        };
+        ArrayExpressionInner::Slice(box array, box from, box to).annotate(ty, size)
+    }
+}
+
 impl<'ast, T> Select<'ast, T> for FieldElementExpression<'ast, T> {
     fn select(array: ArrayExpression<'ast, T>, index: FieldElementExpression<'ast, T>) -> Self {
         FieldElementExpression::Select(box array, box index)
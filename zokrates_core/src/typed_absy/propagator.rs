@@ -0,0 +1,267 @@
+// Constant propagation and folding, implemented as a `Folder`. Carries a map of identifiers
+// that are known, at this point in the walk, to be bound to a literal value, and folds
+// expressions bottom-up into their reduced form whenever both operands (or the condition, or
+// the index) are themselves literals. This shrinks the constraint system before flattening.
+//
+// `static_analysis::propagation::Propagator` covers a narrower case of the same idea: it only
+// tracks loop induction variables, to unroll `for` loops with constant bounds. This pass tracks
+// every definition that turns out to be a literal, not just loop indices, and additionally
+// resolves `Select`/`Member` against literal arrays/structs, so it subsumes the arithmetic
+// folding the loop unroller needs as a side effect of a more general walk.
+
+use crate::typed_absy::folder::*;
+use crate::typed_absy::*;
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+pub struct Propagator<'ast, T> {
+    constants: HashMap<Identifier<'ast>, TypedExpression<'ast, T>>,
+}
+
+impl<'ast, T: Field> Propagator<'ast, T> {
+    pub fn propagate(p: TypedProgram<'ast, T>) -> TypedProgram<'ast, T> {
+        Propagator {
+            constants: HashMap::new(),
+        }
+        .fold_program(p)
+    }
+
+    // whether `e` is already a literal of its type, i.e. safe to bind a later read to directly
+    fn is_literal(e: &TypedExpression<'ast, T>) -> bool {
+        match e {
+            TypedExpression::FieldElement(FieldElementExpression::Number(_)) => true,
+            TypedExpression::Boolean(BooleanExpression::Value(_)) => true,
+            TypedExpression::Uint(u) => matches!(&u.inner, UExpressionInner::Value(_)),
+            TypedExpression::Array(a) => matches!(a.as_inner(), ArrayExpressionInner::Value(_)),
+            TypedExpression::Struct(s) => matches!(s.as_inner(), StructExpressionInner::Value(_)),
+        }
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
+    fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
+        match s {
+            TypedStatement::Definition(TypedAssignee::Identifier(v), e) => {
+                let e = self.fold_expression(e);
+                if Self::is_literal(&e) {
+                    self.constants.insert(v.id.clone(), e.clone());
+                } else {
+                    self.constants.remove(&v.id);
+                }
+                vec![TypedStatement::Definition(TypedAssignee::Identifier(v), e)]
+            }
+            // a loop body may run any number of times, so no binding is trusted inside or after it
+            s @ TypedStatement::For(..) => {
+                self.constants.clear();
+                let folded = fold_statement(self, s);
+                self.constants.clear();
+                folded
+            }
+            // a write through an index, member or slice, or a multiple definition, can change
+            // a variable without rebinding it to a literal here
+            s @ TypedStatement::Definition(..) | s @ TypedStatement::MultipleDefinition(..) => {
+                let folded = fold_statement(self, s);
+                self.constants.clear();
+                folded
+            }
+            s => fold_statement(self, s),
+        }
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        match fold_field_expression(self, e) {
+            FieldElementExpression::Identifier(id) => match self.constants.get(&id) {
+                Some(TypedExpression::FieldElement(e)) => e.clone(),
+                _ => FieldElementExpression::Identifier(id),
+            },
+            FieldElementExpression::Add(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                FieldElementExpression::Number(n1 + n2)
+            }
+            FieldElementExpression::Sub(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                FieldElementExpression::Number(n1 - n2)
+            }
+            FieldElementExpression::Mult(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                FieldElementExpression::Number(n1 * n2)
+            }
+            FieldElementExpression::Div(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2))
+                if n2 != T::zero() =>
+            {
+                FieldElementExpression::Number(n1 / n2)
+            }
+            FieldElementExpression::Pow(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                match n2.to_dec_string().parse::<usize>() {
+                    Ok(exp) => {
+                        let mut acc = T::one();
+                        for _ in 0..exp {
+                            acc = acc * n1.clone();
+                        }
+                        FieldElementExpression::Number(acc)
+                    }
+                    Err(_) => FieldElementExpression::Pow(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)),
+                }
+            }
+            FieldElementExpression::IfElse(box BooleanExpression::Value(true), box consequence, _) => consequence,
+            FieldElementExpression::IfElse(box BooleanExpression::Value(false), _, box alternative) => alternative,
+            FieldElementExpression::Select(box array, box FieldElementExpression::Number(n)) => {
+                match (array.as_inner(), n.to_dec_string().parse::<usize>()) {
+                    (ArrayExpressionInner::Value(values), Ok(i)) => match values.get(i) {
+                        Some(TypedExpression::FieldElement(e)) => e.clone(),
+                        _ => FieldElementExpression::Select(box array, box FieldElementExpression::Number(n)),
+                    },
+                    _ => FieldElementExpression::Select(box array, box FieldElementExpression::Number(n)),
+                }
+            }
+            FieldElementExpression::Member(box s, id) => {
+                match (s.as_inner(), s.ty().iter().position(|m| m.id == id)) {
+                    (StructExpressionInner::Value(values), Some(i)) => match values.get(i) {
+                        Some(TypedExpression::FieldElement(e)) => e.clone(),
+                        _ => FieldElementExpression::Member(box s, id),
+                    },
+                    _ => FieldElementExpression::Member(box s, id),
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        match fold_boolean_expression(self, e) {
+            BooleanExpression::Identifier(id) => match self.constants.get(&id) {
+                Some(TypedExpression::Boolean(e)) => e.clone(),
+                _ => BooleanExpression::Identifier(id),
+            },
+            BooleanExpression::FieldEq(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                BooleanExpression::Value(n1 == n2)
+            }
+            BooleanExpression::UintEq(box e1, box e2) => match (&e1.inner, &e2.inner) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => BooleanExpression::Value(v1 == v2),
+                _ => BooleanExpression::UintEq(box e1, box e2),
+            },
+            BooleanExpression::Lt(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                BooleanExpression::Value(n1 < n2)
+            }
+            BooleanExpression::Le(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                BooleanExpression::Value(n1 <= n2)
+            }
+            BooleanExpression::Gt(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                BooleanExpression::Value(n1 > n2)
+            }
+            BooleanExpression::Ge(box FieldElementExpression::Number(n1), box FieldElementExpression::Number(n2)) => {
+                BooleanExpression::Value(n1 >= n2)
+            }
+            BooleanExpression::And(box BooleanExpression::Value(v1), box BooleanExpression::Value(v2)) => {
+                BooleanExpression::Value(v1 && v2)
+            }
+            BooleanExpression::And(box BooleanExpression::Value(false), _)
+            | BooleanExpression::And(_, box BooleanExpression::Value(false)) => BooleanExpression::Value(false),
+            BooleanExpression::Or(box BooleanExpression::Value(v1), box BooleanExpression::Value(v2)) => {
+                BooleanExpression::Value(v1 || v2)
+            }
+            BooleanExpression::Or(box BooleanExpression::Value(true), _)
+            | BooleanExpression::Or(_, box BooleanExpression::Value(true)) => BooleanExpression::Value(true),
+            BooleanExpression::Not(box BooleanExpression::Value(v)) => BooleanExpression::Value(!v),
+            BooleanExpression::IfElse(box BooleanExpression::Value(true), box consequence, _) => consequence,
+            BooleanExpression::IfElse(box BooleanExpression::Value(false), _, box alternative) => alternative,
+            BooleanExpression::Select(box array, box FieldElementExpression::Number(n)) => {
+                match (array.as_inner(), n.to_dec_string().parse::<usize>()) {
+                    (ArrayExpressionInner::Value(values), Ok(i)) => match values.get(i) {
+                        Some(TypedExpression::Boolean(e)) => e.clone(),
+                        _ => BooleanExpression::Select(box array, box FieldElementExpression::Number(n)),
+                    },
+                    _ => BooleanExpression::Select(box array, box FieldElementExpression::Number(n)),
+                }
+            }
+            BooleanExpression::Member(box s, id) => {
+                match (s.as_inner(), s.ty().iter().position(|m| m.id == id)) {
+                    (StructExpressionInner::Value(values), Some(i)) => match values.get(i) {
+                        Some(TypedExpression::Boolean(e)) => e.clone(),
+                        _ => BooleanExpression::Member(box s, id),
+                    },
+                    _ => BooleanExpression::Member(box s, id),
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match fold_uint_expression_inner(self, bitwidth, e) {
+            UExpressionInner::Identifier(id) => match self.constants.get(&id) {
+                Some(TypedExpression::Uint(e)) => e.inner.clone(),
+                _ => UExpressionInner::Identifier(id),
+            },
+            UExpressionInner::IfElse(box BooleanExpression::Value(true), box consequence, _) => consequence.inner,
+            UExpressionInner::IfElse(box BooleanExpression::Value(false), _, box alternative) => alternative.inner,
+            UExpressionInner::Select(box array, box FieldElementExpression::Number(n)) => {
+                match (array.as_inner(), n.to_dec_string().parse::<usize>()) {
+                    (ArrayExpressionInner::Value(values), Ok(i)) => match values.get(i) {
+                        Some(TypedExpression::Uint(e)) => e.inner.clone(),
+                        _ => UExpressionInner::Select(box array, box FieldElementExpression::Number(n)),
+                    },
+                    _ => UExpressionInner::Select(box array, box FieldElementExpression::Number(n)),
+                }
+            }
+            UExpressionInner::Member(box s, id) => {
+                match (s.as_inner(), s.ty().iter().position(|m| m.id == id)) {
+                    (StructExpressionInner::Value(values), Some(i)) => match values.get(i) {
+                        Some(TypedExpression::Uint(e)) => e.inner.clone(),
+                        _ => UExpressionInner::Member(box s, id),
+                    },
+                    _ => UExpressionInner::Member(box s, id),
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_array_expression_inner(
+        &mut self,
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
+        match fold_array_expression_inner(self, ty, size, e) {
+            ArrayExpressionInner::Identifier(id) => match self.constants.get(&id) {
+                Some(TypedExpression::Array(e)) => e.as_inner().clone(),
+                _ => ArrayExpressionInner::Identifier(id),
+            },
+            ArrayExpressionInner::IfElse(box BooleanExpression::Value(true), box consequence, _) => {
+                consequence.inner
+            }
+            ArrayExpressionInner::IfElse(box BooleanExpression::Value(false), _, box alternative) => {
+                alternative.inner
+            }
+            e => e,
+        }
+    }
+
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        match fold_struct_expression_inner(self, ty, e) {
+            StructExpressionInner::Identifier(id) => match self.constants.get(&id) {
+                Some(TypedExpression::Struct(e)) => e.as_inner().clone(),
+                _ => StructExpressionInner::Identifier(id),
+            },
+            StructExpressionInner::IfElse(box BooleanExpression::Value(true), box consequence, _) => {
+                consequence.inner
+            }
+            StructExpressionInner::IfElse(box BooleanExpression::Value(false), _, box alternative) => {
+                alternative.inner
+            }
+            e => e,
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Structural search-and-replace over `UExpression`, for constraint-simplification rules such
+//! as `$x ^ $x ==>> 0u32` or `($a | $b) & $b ==>> $b`. This is scoped to the bitwise/identifier/
+//! literal subset of the typed AST that those rules actually need, rather than every
+//! `TypedExpression` variant: a general-purpose rewriter over the full AST (arrays, structs,
+//! function calls) would need a much larger pattern language, and nothing downstream needs it
+//! yet. Widening the pattern language to more node kinds can follow the same shape as `Pattern`
+//! below once a concrete rule needs it.
+//!
+//! A rule's two halves are parsed from a small textual grammar built only for patterns:
+//! identifiers starting with `$` are metavariables, bare digit sequences (optionally followed by
+//! `u8`/`u16`/`u32`) are literals, and `|`, `&`, `^`, `!` and parentheses combine them with the
+//! usual bitwise precedence (`|` loosest, then `^`, then `&`, then unary `!`).
+
+use crate::typed_absy::folder::Folder;
+use crate::typed_absy::*;
+use std::collections::HashMap;
+use std::fmt;
+use zokrates_field::Field;
+
+/// A node in a parsed pattern or replacement template.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// A metavariable (`$x`): binds to any subtree on first occurrence, and must structurally
+    /// equal any later occurrence of the same name within one match.
+    Var(String),
+    /// A literal constant, width-agnostic until it is instantiated against a matched root.
+    Literal(u128),
+    Or(Box<Pattern>, Box<Pattern>),
+    And(Box<Pattern>, Box<Pattern>),
+    Xor(Box<Pattern>, Box<Pattern>),
+    Not(Box<Pattern>),
+}
+
+/// Why parsing a `pattern ==>> replacement` rule failed.
+#[derive(Debug, PartialEq)]
+pub enum RuleError {
+    /// The rule text did not contain the `==>>` delimiter.
+    NoDelimiter,
+    /// The rule text contained more than one `==>>` delimiter.
+    MultipleDelimiters,
+    /// The same metavariable name was bound twice on the left-hand side.
+    DuplicateMetavariable(String),
+    /// The pattern or replacement text could not be parsed as a bitwise expression.
+    Syntax(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::NoDelimiter => write!(f, "rule is missing the `==>>` delimiter"),
+            RuleError::MultipleDelimiters => {
+                write!(f, "rule contains more than one `==>>` delimiter")
+            }
+            RuleError::DuplicateMetavariable(name) => {
+                write!(f, "metavariable `{}` is bound twice on the left-hand side", name)
+            }
+            RuleError::Syntax(msg) => write!(f, "syntax error: {}", msg),
+        }
+    }
+}
+
+/// A parsed rewrite rule, ready to be matched against a `UExpression` tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub pattern: Pattern,
+    pub replacement: Pattern,
+}
+
+impl Rule {
+    /// Parse `"pattern ==>> replacement"` into a `Rule`.
+    pub fn parse(source: &str) -> Result<Self, RuleError> {
+        let mut parts = source.split("==>>");
+        let pattern_src = parts.next().ok_or(RuleError::NoDelimiter)?;
+        let replacement_src = match parts.next() {
+            Some(r) => r,
+            None => return Err(RuleError::NoDelimiter),
+        };
+        if parts.next().is_some() {
+            return Err(RuleError::MultipleDelimiters);
+        }
+
+        let pattern = parse_pattern(pattern_src)?;
+        let replacement = parse_pattern(replacement_src)?;
+
+        let mut seen = std::collections::HashSet::new();
+        check_no_duplicate_metavariables(&pattern, &mut seen)?;
+
+        // every metavariable the replacement uses must be bound by the pattern
+        let mut stack = vec![&replacement];
+        while let Some(p) = stack.pop() {
+            match p {
+                Pattern::Var(name) if !seen.contains(name) => {
+                    return Err(RuleError::Syntax(format!(
+                        "metavariable `{}` is not bound by the pattern",
+                        name
+                    )))
+                }
+                Pattern::Var(_) | Pattern::Literal(_) => {}
+                Pattern::Or(l, r) | Pattern::And(l, r) | Pattern::Xor(l, r) => {
+                    stack.push(l);
+                    stack.push(r);
+                }
+                Pattern::Not(e) => stack.push(e),
+            }
+        }
+
+        Ok(Rule {
+            pattern,
+            replacement,
+        })
+    }
+}
+
+fn check_no_duplicate_metavariables(
+    pattern: &Pattern,
+    seen: &mut std::collections::HashSet<String>,
+) -> Result<(), RuleError> {
+    match pattern {
+        Pattern::Var(name) => {
+            if !seen.insert(name.clone()) {
+                return Err(RuleError::DuplicateMetavariable(name.clone()));
+            }
+            Ok(())
+        }
+        Pattern::Literal(_) => Ok(()),
+        Pattern::Or(l, r) | Pattern::And(l, r) | Pattern::Xor(l, r) => {
+            check_no_duplicate_metavariables(l, seen)?;
+            check_no_duplicate_metavariables(r, seen)
+        }
+        Pattern::Not(e) => check_no_duplicate_metavariables(e, seen),
+    }
+}
+
+// A small recursive-descent parser for the pattern grammar described in the module doc comment.
+struct PatternParser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+fn tokenize(source: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_whitespace() {
+            i += 1;
+        } else if "|&^!()".contains(c) {
+            tokens.push(&source[i..i + 1]);
+            i += 1;
+        } else {
+            let start = i;
+            while i < bytes.len() && !(bytes[i] as char).is_whitespace() && !"|&^!()".contains(bytes[i] as char) {
+                i += 1;
+            }
+            tokens.push(&source[start..i]);
+        }
+    }
+    tokens
+}
+
+impl<'a> PatternParser<'a> {
+    fn new(source: &'a str) -> Self {
+        PatternParser {
+            tokens: tokenize(source),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<&'a str> {
+        let t = self.peek();
+        self.pos += 1;
+        t
+    }
+
+    // or := xor ('|' xor)*
+    fn parse_or(&mut self) -> Result<Pattern, RuleError> {
+        let mut left = self.parse_xor()?;
+        while self.peek() == Some("|") {
+            self.bump();
+            let right = self.parse_xor()?;
+            left = Pattern::Or(box left, box right);
+        }
+        Ok(left)
+    }
+
+    // xor := and ('^' and)*
+    fn parse_xor(&mut self) -> Result<Pattern, RuleError> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some("^") {
+            self.bump();
+            let right = self.parse_and()?;
+            left = Pattern::Xor(box left, box right);
+        }
+        Ok(left)
+    }
+
+    // and := unary ('&' unary)*
+    fn parse_and(&mut self) -> Result<Pattern, RuleError> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some("&") {
+            self.bump();
+            let right = self.parse_unary()?;
+            left = Pattern::And(box left, box right);
+        }
+        Ok(left)
+    }
+
+    // unary := '!' unary | atom
+    fn parse_unary(&mut self) -> Result<Pattern, RuleError> {
+        if self.peek() == Some("!") {
+            self.bump();
+            let inner = self.parse_unary()?;
+            Ok(Pattern::Not(box inner))
+        } else {
+            self.parse_atom()
+        }
+    }
+
+    // atom := '(' or ')' | '$' ident | literal
+    fn parse_atom(&mut self) -> Result<Pattern, RuleError> {
+        match self.bump() {
+            Some("(") => {
+                let inner = self.parse_or()?;
+                match self.bump() {
+                    Some(")") => Ok(inner),
+                    _ => Err(RuleError::Syntax("expected closing `)`".to_string())),
+                }
+            }
+            Some(tok) if tok.starts_with('$') => Ok(Pattern::Var(tok[1..].to_string())),
+            Some(tok) => parse_literal(tok)
+                .map(Pattern::Literal)
+                .ok_or_else(|| RuleError::Syntax(format!("unexpected token `{}`", tok))),
+            None => Err(RuleError::Syntax("unexpected end of input".to_string())),
+        }
+    }
+}
+
+fn parse_literal(tok: &str) -> Option<u128> {
+    let digits = tok
+        .trim_end_matches("u8")
+        .trim_end_matches("u16")
+        .trim_end_matches("u32");
+    if let Some(hex) = digits.strip_prefix("0x") {
+        u128::from_str_radix(hex, 16).ok()
+    } else {
+        digits.parse::<u128>().ok()
+    }
+}
+
+fn parse_pattern(source: &str) -> Result<Pattern, RuleError> {
+    let mut parser = PatternParser::new(source);
+    let pattern = parser.parse_or()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(RuleError::Syntax(format!(
+            "unexpected trailing token `{}`",
+            parser.tokens[parser.pos]
+        )));
+    }
+    Ok(pattern)
+}
+
+/// Try to match `pattern` against `expr`, extending `bindings` with any metavariables bound
+/// along the way. A metavariable that already has a binding must structurally equal the
+/// previously-bound subtree rather than re-binding, so `$x ^ $x` only matches when both sides
+/// really are the same expression.
+fn match_pattern<'ast, T: Field>(
+    pattern: &Pattern,
+    expr: &UExpression<'ast, T>,
+    bindings: &mut HashMap<String, UExpression<'ast, T>>,
+) -> bool {
+    match pattern {
+        Pattern::Var(name) => match bindings.get(name) {
+            Some(bound) => bound == expr,
+            None => {
+                bindings.insert(name.clone(), expr.clone());
+                true
+            }
+        },
+        Pattern::Literal(v) => matches!(expr.as_inner(), UExpressionInner::Value(n) if n == v),
+        Pattern::Or(l, r) => match expr.as_inner() {
+            UExpressionInner::Or(left, right) => {
+                match_pattern(l, left, bindings) && match_pattern(r, right, bindings)
+            }
+            _ => false,
+        },
+        Pattern::And(l, r) => match expr.as_inner() {
+            UExpressionInner::And(left, right) => {
+                match_pattern(l, left, bindings) && match_pattern(r, right, bindings)
+            }
+            _ => false,
+        },
+        Pattern::Xor(l, r) => match expr.as_inner() {
+            UExpressionInner::Xor(left, right) => {
+                match_pattern(l, left, bindings) && match_pattern(r, right, bindings)
+            }
+            _ => false,
+        },
+        Pattern::Not(e) => match expr.as_inner() {
+            UExpressionInner::Not(inner) => match_pattern(e, inner, bindings),
+            _ => false,
+        },
+    }
+}
+
+/// Build a concrete `UExpression` from `pattern` by substituting `bindings`, annotating any
+/// freshly-built literal or operator node with `bitwidth` (the width of the subtree being
+/// replaced, so the rewrite can never change an expression's type).
+fn instantiate<'ast, T: Field>(
+    pattern: &Pattern,
+    bindings: &HashMap<String, UExpression<'ast, T>>,
+    bitwidth: UBitwidth,
+) -> UExpression<'ast, T> {
+    match pattern {
+        Pattern::Var(name) => bindings
+            .get(name)
+            .cloned()
+            .expect("replacement referenced an unbound metavariable; Rule::parse should have caught this"),
+        Pattern::Literal(v) => UExpressionInner::Value(*v).annotate(bitwidth),
+        Pattern::Or(l, r) => {
+            let l = instantiate(l, bindings, bitwidth);
+            let r = instantiate(r, bindings, bitwidth);
+            UExpressionInner::Or(box l, box r).annotate(bitwidth)
+        }
+        Pattern::And(l, r) => {
+            let l = instantiate(l, bindings, bitwidth);
+            let r = instantiate(r, bindings, bitwidth);
+            UExpressionInner::And(box l, box r).annotate(bitwidth)
+        }
+        Pattern::Xor(l, r) => {
+            let l = instantiate(l, bindings, bitwidth);
+            let r = instantiate(r, bindings, bitwidth);
+            UExpressionInner::Xor(box l, box r).annotate(bitwidth)
+        }
+        Pattern::Not(e) => {
+            let e = instantiate(e, bindings, bitwidth);
+            UExpressionInner::Not(box e).annotate(bitwidth)
+        }
+    }
+}
+
+/// One successful application of a rule, as reported by `MatchFinder`. Typed expressions don't
+/// carry source positions once they're past the checker (only the transient `Spanned` wrapper
+/// used during inline-array checking does, see `typed_absy::span`), so `pos` is `None` here;
+/// callers that need a span should apply rules earlier, against the positioned tree, instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub rule: Rule,
+    pub pos: Option<(usize, usize)>,
+}
+
+/// Walks a typed AST bottom-up, applying the first matching rule at each `UExpression` node and
+/// recording every rewrite it makes. Re-running under the existing checker is unnecessary here:
+/// every instantiated replacement is built with the same `bitwidth` as the subtree it replaces
+/// (see `instantiate`), so the result is well-typed by construction rather than by re-checking.
+pub struct MatchFinder<'r> {
+    rules: &'r [Rule],
+    pub edits: Vec<Edit>,
+}
+
+impl<'r> MatchFinder<'r> {
+    pub fn new(rules: &'r [Rule]) -> Self {
+        MatchFinder {
+            rules,
+            edits: vec![],
+        }
+    }
+
+    fn try_rewrite<'ast, T: Field>(&mut self, e: &UExpression<'ast, T>) -> Option<UExpression<'ast, T>> {
+        for rule in self.rules {
+            let mut bindings = HashMap::new();
+            if match_pattern(&rule.pattern, e, &mut bindings) {
+                let rewritten = instantiate(&rule.replacement, &bindings, e.bitwidth());
+                self.edits.push(Edit {
+                    rule: rule.clone(),
+                    pos: None,
+                });
+                return Some(rewritten);
+            }
+        }
+        None
+    }
+}
+
+impl<'ast, 'r, T: Field> Folder<'ast, T> for MatchFinder<'r> {
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> UExpression<'ast, T> {
+        // fold children first, so a nested match (e.g. the `$x` in `$x ^ $x`) has already been
+        // simplified by the time the parent is tried
+        let e = crate::typed_absy::folder::fold_uint_expression(self, e);
+        match self.try_rewrite(&e) {
+            Some(rewritten) => rewritten,
+            None => e,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Symbolic array sizes and unification over size variables.
+//!
+//! Array types currently carry a concrete `usize` length, which forces every
+//! array dimension to be known when a node is built. To type-check generic
+//! code (e.g. a function returning `field[N]` for a caller-chosen `N`) we need
+//! to reason about sizes that are not yet known, and to discover equalities
+//! between them by unification, just like the type checker unifies element
+//! types.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// An array size, either a known constant or a size variable to be resolved by
+/// unification.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Size {
+    Concrete(usize),
+    Variable(usize),
+}
+
+impl From<usize> for Size {
+    fn from(n: usize) -> Size {
+        Size::Concrete(n)
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Size::Concrete(n) => write!(f, "{}", n),
+            Size::Variable(i) => write!(f, "N{}", i),
+        }
+    }
+}
+
+/// A union-find table mapping size variables to the size they were unified
+/// with. Resolution follows chains of variable bindings down to a
+/// representative, which may itself be concrete.
+#[derive(Default)]
+pub struct SizeUnifier {
+    next: usize,
+    bindings: HashMap<usize, Size>,
+}
+
+impl SizeUnifier {
+    pub fn new() -> Self {
+        SizeUnifier::default()
+    }
+
+    /// Allocate a fresh, unconstrained size variable.
+    pub fn fresh(&mut self) -> Size {
+        let v = self.next;
+        self.next += 1;
+        Size::Variable(v)
+    }
+
+    /// Follow variable bindings to the current representative of `size`.
+    pub fn resolve(&self, size: &Size) -> Size {
+        let mut current = size.clone();
+        while let Size::Variable(v) = current {
+            match self.bindings.get(&v) {
+                Some(next) => current = next.clone(),
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// Unify two sizes, recording a binding when one is an unbound variable.
+    /// Returns `Err` when two concrete sizes disagree.
+    pub fn unify(&mut self, a: &Size, b: &Size) -> Result<Size, (usize, usize)> {
+        let a = self.resolve(a);
+        let b = self.resolve(b);
+        match (a, b) {
+            (Size::Concrete(x), Size::Concrete(y)) => {
+                if x == y {
+                    Ok(Size::Concrete(x))
+                } else {
+                    Err((x, y))
+                }
+            }
+            (Size::Variable(v), Size::Variable(w)) if v == w => Ok(Size::Variable(v)),
+            (Size::Variable(v), other) | (other, Size::Variable(v)) => {
+                self.bindings.insert(v, other.clone());
+                Ok(other)
+            }
+        }
+    }
+}
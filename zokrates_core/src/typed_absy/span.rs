@@ -0,0 +1,99 @@
+//! Source-location spans for typed AST nodes.
+//!
+//! The typed AST is built by the semantic checker from the positioned `absy`
+//! tree (see `absy::Node`), but the span information is dropped during
+//! checking. To produce diagnostics that point back at the original source, we
+//! carry a `Span` alongside typed values with the lightweight `Spanned<T>`
+//! wrapper, mirroring `absy::Node<T>` but without the parser dependency.
+
+use crate::parser::Position;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// A half-open source range, from the start of the first token to the end of
+/// the last, in the file the node was checked from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Span { start, end }
+    }
+
+    /// The smallest span covering both `self` and `other`.
+    pub fn merge(self, other: Span) -> Span {
+        Span {
+            start: std::cmp::min(self.start, other.start),
+            end: std::cmp::max(self.end, other.end),
+        }
+    }
+}
+
+/// A typed value annotated with the source range it was checked from.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    pub span: Span,
+    pub value: T,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(span: Span, value: T) -> Self {
+        Spanned { span, value }
+    }
+}
+
+/// Anything that knows the source range it originated from.
+pub trait WithSpan {
+    fn span(&self) -> Span;
+}
+
+impl<T> WithSpan for Spanned<T> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
+/// Attach a span to any typed expression (or sub-expression) so diagnostics
+/// can point at the exact source range it came from. Blanket-implemented for
+/// every value, letting the checker write `e.spanned(span)` uniformly across
+/// `FieldElementExpression`, `BooleanExpression`, `ArrayExpression`, etc.
+pub trait IntoSpanned: Sized {
+    fn spanned(self, span: Span) -> Spanned<Self> {
+        Spanned::new(span, self)
+    }
+
+    fn at(self, start: Position, end: Position) -> Spanned<Self> {
+        self.spanned(Span::new(start, end))
+    }
+}
+
+impl<T> IntoSpanned for T {}
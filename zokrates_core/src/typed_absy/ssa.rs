@@ -0,0 +1,99 @@
+// Single static assignment renaming, implemented as a `Folder`. Every `Identifier` that gets
+// written is given a fresh `version`, and every read is rewritten to the version that was live
+// at that point in the walk, so downstream passes (constant propagation, constraint generation)
+// can treat each `(id, version)` pair as referring to exactly one definition. `IfElse` is only
+// ever an expression in this AST (there is no statement-level branch), so its branches are pure
+// and need no reconciliation beyond folding them like any other subexpression.
+
+use crate::typed_absy::folder::{fold_assignee, fold_statement, Folder};
+use crate::typed_absy::*;
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+#[derive(Default)]
+pub struct Ssa<'ast> {
+    versions: HashMap<CoreIdentifier<'ast>, usize>,
+}
+
+impl<'ast> Ssa<'ast> {
+    pub fn transform<T: Field>(p: TypedProgram<'ast, T>) -> TypedProgram<'ast, T> {
+        Ssa::default().fold_program(p)
+    }
+
+    fn current_version(&self, id: &CoreIdentifier<'ast>) -> usize {
+        self.versions.get(id).cloned().unwrap_or(0)
+    }
+
+    // introduces a new version for `id`, making it the one subsequent reads resolve to
+    fn issue_version(&mut self, id: &CoreIdentifier<'ast>) -> usize {
+        let version = self.current_version(id) + 1;
+        self.versions.insert(id.clone(), version);
+        version
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Ssa<'ast> {
+    fn fold_name(&mut self, n: Identifier<'ast>) -> Identifier<'ast> {
+        let version = self.current_version(&n.id);
+        Identifier { version, ..n }
+    }
+
+    fn fold_assignee(&mut self, a: TypedAssignee<'ast, T>) -> TypedAssignee<'ast, T> {
+        match a {
+            TypedAssignee::Identifier(v) => {
+                let version = self.issue_version(&v.id.id);
+                TypedAssignee::Identifier(Variable {
+                    id: Identifier { version, ..v.id },
+                    ..v
+                })
+            }
+            a => fold_assignee(self, a),
+        }
+    }
+
+    fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
+        match s {
+            // the right-hand side is folded before the assignee, so a read of the same
+            // identifier on both sides of `=` (e.g. `a = a + 1`) resolves to the version that
+            // was live *before* this statement bumps it, not the version it is about to become
+            TypedStatement::Definition(assignee, expression) => {
+                let expression = self.fold_expression(expression);
+                let assignee = self.fold_assignee(assignee);
+                vec![TypedStatement::Definition(assignee, expression)]
+            }
+            TypedStatement::MultipleDefinition(assignees, expression_list) => {
+                let expression_list = self.fold_expression_list(expression_list);
+                let assignees = assignees
+                    .into_iter()
+                    .map(|a| self.fold_assignee(a))
+                    .collect();
+                vec![TypedStatement::MultipleDefinition(assignees, expression_list)]
+            }
+            // loop bounds are static, so the body below stands for a single pass through the
+            // loop; the index gets its own fresh version on entry, and any variable written
+            // inside keeps carrying its version forward afterwards, since code following the
+            // loop must see the value the last iteration produced. There is no phi/branch node
+            // in this AST to join "ran zero times" against "ran at least once" more precisely
+            // than that — full reconciliation falls out once loop unrolling expands each
+            // iteration into its own statements and re-runs this fold over the result.
+            TypedStatement::For(v, from, to, statements) => {
+                let index_version = self.issue_version(&v.id.id);
+                let v = Variable {
+                    id: Identifier {
+                        version: index_version,
+                        ..v.id
+                    },
+                    ..v
+                };
+
+                let statements = statements
+                    .into_iter()
+                    .flat_map(|s| self.fold_statement(s))
+                    .collect();
+
+                vec![TypedStatement::For(v, from, to, statements)]
+            }
+            s => fold_statement(self, s),
+        }
+    }
+}
@@ -0,0 +1,423 @@
+// A read-only walk through a typed AST, for passes that only inspect the tree instead of
+// rebuilding it (e.g. collecting called `FunctionKey`s, counting subexpressions, gathering the
+// identifiers that are actually read). Mirrors the structure of `Folder`, but every `visit_*`
+// method borrows its node and returns `()`; the default free functions recurse into children.
+
+use crate::typed_absy::*;
+use zokrates_field::Field;
+
+pub trait Visitor<'ast, T: Field> {
+    fn visit_program(&mut self, p: &TypedProgram<'ast, T>) {
+        visit_program(self, p)
+    }
+
+    fn visit_module(&mut self, p: &TypedModule<'ast, T>) {
+        visit_module(self, p)
+    }
+
+    fn visit_function_symbol(&mut self, s: &TypedFunctionSymbol<'ast, T>) {
+        visit_function_symbol(self, s)
+    }
+
+    fn visit_function(&mut self, f: &TypedFunction<'ast, T>) {
+        visit_function(self, f)
+    }
+
+    fn visit_parameter(&mut self, p: &Parameter<'ast>) {
+        self.visit_variable(&p.id)
+    }
+
+    fn visit_name(&mut self, _n: &Identifier<'ast>) {}
+
+    fn visit_variable(&mut self, v: &Variable<'ast>) {
+        self.visit_name(&v.id)
+    }
+
+    fn visit_assignee(&mut self, a: &TypedAssignee<'ast, T>) {
+        visit_assignee(self, a)
+    }
+
+    fn visit_statement(&mut self, s: &TypedStatement<'ast, T>) {
+        visit_statement(self, s)
+    }
+
+    fn visit_expression(&mut self, e: &TypedExpression<'ast, T>) {
+        match e {
+            TypedExpression::FieldElement(e) => self.visit_field_expression(e),
+            TypedExpression::Boolean(e) => self.visit_boolean_expression(e),
+            TypedExpression::Uint(e) => self.visit_uint_expression(e),
+            TypedExpression::Array(e) => self.visit_array_expression(e),
+            TypedExpression::Struct(e) => self.visit_struct_expression(e),
+        }
+    }
+
+    fn visit_array_expression(&mut self, e: &ArrayExpression<'ast, T>) {
+        visit_array_expression(self, e)
+    }
+
+    fn visit_struct_expression(&mut self, e: &StructExpression<'ast, T>) {
+        visit_struct_expression(self, e)
+    }
+
+    fn visit_expression_list(&mut self, es: &TypedExpressionList<'ast, T>) {
+        match es {
+            TypedExpressionList::FunctionCall(_, arguments, _) => {
+                for a in arguments {
+                    self.visit_expression(a)
+                }
+            }
+        }
+    }
+
+    fn visit_field_expression(&mut self, e: &FieldElementExpression<'ast, T>) {
+        visit_field_expression(self, e)
+    }
+
+    fn visit_boolean_expression(&mut self, e: &BooleanExpression<'ast, T>) {
+        visit_boolean_expression(self, e)
+    }
+
+    fn visit_uint_expression(&mut self, e: &UExpression<'ast, T>) {
+        visit_uint_expression(self, e)
+    }
+
+    fn visit_uint_expression_inner(&mut self, bitwidth: UBitwidth, e: &UExpressionInner<'ast, T>) {
+        visit_uint_expression_inner(self, bitwidth, e)
+    }
+
+    fn visit_array_expression_inner(
+        &mut self,
+        ty: &Type,
+        size: usize,
+        e: &ArrayExpressionInner<'ast, T>,
+    ) {
+        visit_array_expression_inner(self, ty, size, e)
+    }
+
+    fn visit_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: &StructExpressionInner<'ast, T>,
+    ) {
+        visit_struct_expression_inner(self, ty, e)
+    }
+}
+
+pub fn visit_program<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    p: &TypedProgram<'ast, T>,
+) {
+    for module in p.modules.values() {
+        v.visit_module(module)
+    }
+}
+
+pub fn visit_module<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    p: &TypedModule<'ast, T>,
+) {
+    for fun in p.functions.values() {
+        v.visit_function_symbol(fun)
+    }
+}
+
+pub fn visit_function_symbol<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    s: &TypedFunctionSymbol<'ast, T>,
+) {
+    if let TypedFunctionSymbol::Here(fun) = s {
+        v.visit_function(fun)
+    }
+    // by default, do not visit modules recursively
+}
+
+pub fn visit_function<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    f: &TypedFunction<'ast, T>,
+) {
+    for a in &f.arguments {
+        v.visit_parameter(a)
+    }
+    for s in &f.statements {
+        v.visit_statement(s)
+    }
+}
+
+pub fn visit_assignee<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    a: &TypedAssignee<'ast, T>,
+) {
+    match a {
+        TypedAssignee::Identifier(variable) => v.visit_variable(variable),
+        TypedAssignee::Select(box a, box index) => {
+            v.visit_assignee(a);
+            v.visit_field_expression(index);
+        }
+        TypedAssignee::Member(box s, _) => v.visit_assignee(s),
+        TypedAssignee::Slice(box a, box lo, box hi) => {
+            v.visit_assignee(a);
+            v.visit_field_expression(lo);
+            v.visit_field_expression(hi);
+        }
+    }
+}
+
+pub fn visit_statement<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    s: &TypedStatement<'ast, T>,
+) {
+    match s {
+        TypedStatement::Return(expressions) => {
+            for e in expressions {
+                v.visit_expression(e)
+            }
+        }
+        TypedStatement::Definition(a, e) => {
+            v.visit_assignee(a);
+            v.visit_expression(e);
+        }
+        TypedStatement::Declaration(variable) => v.visit_variable(variable),
+        TypedStatement::Assertion(e) => v.visit_boolean_expression(e),
+        TypedStatement::For(variable, _, _, statements) => {
+            v.visit_variable(variable);
+            for s in statements {
+                v.visit_statement(s)
+            }
+        }
+        TypedStatement::MultipleDefinition(assignees, elist) => {
+            for a in assignees {
+                v.visit_assignee(a)
+            }
+            v.visit_expression_list(elist);
+        }
+    }
+}
+
+pub fn visit_array_expression_inner<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    _: &Type,
+    _: usize,
+    e: &ArrayExpressionInner<'ast, T>,
+) {
+    match e {
+        ArrayExpressionInner::Identifier(id) => v.visit_name(id),
+        ArrayExpressionInner::Value(exprs) => {
+            for e in exprs {
+                v.visit_expression(e)
+            }
+        }
+        ArrayExpressionInner::FunctionCall(_, exps) => {
+            for e in exps {
+                v.visit_expression(e)
+            }
+        }
+        ArrayExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+            v.visit_boolean_expression(condition);
+            v.visit_array_expression(consequence);
+            v.visit_array_expression(alternative);
+        }
+        ArrayExpressionInner::Member(box s, _) => v.visit_struct_expression(s),
+        ArrayExpressionInner::Select(box array, box index) => {
+            v.visit_array_expression(array);
+            v.visit_field_expression(index);
+        }
+        ArrayExpressionInner::Repeat(box e, _) => v.visit_expression(e),
+        ArrayExpressionInner::Spread(elements) => {
+            for e in elements {
+                match e {
+                    ArraySpreadElement::Element(e) => v.visit_expression(e),
+                    ArraySpreadElement::Spread(a) => v.visit_array_expression(a),
+                }
+            }
+        }
+        ArrayExpressionInner::Slice(box array, box from, box to) => {
+            v.visit_array_expression(array);
+            v.visit_field_expression(from);
+            v.visit_field_expression(to);
+        }
+    }
+}
+
+pub fn visit_struct_expression_inner<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    _: &StructType,
+    e: &StructExpressionInner<'ast, T>,
+) {
+    match e {
+        StructExpressionInner::Identifier(id) => v.visit_name(id),
+        StructExpressionInner::Value(exprs) => {
+            for e in exprs {
+                v.visit_expression(e)
+            }
+        }
+        StructExpressionInner::FunctionCall(_, exps) => {
+            for e in exps {
+                v.visit_expression(e)
+            }
+        }
+        StructExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+            v.visit_boolean_expression(condition);
+            v.visit_struct_expression(consequence);
+            v.visit_struct_expression(alternative);
+        }
+        StructExpressionInner::Member(box s, _) => v.visit_struct_expression(s),
+        StructExpressionInner::Select(box array, box index) => {
+            v.visit_array_expression(array);
+            v.visit_field_expression(index);
+        }
+    }
+}
+
+pub fn visit_field_expression<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    e: &FieldElementExpression<'ast, T>,
+) {
+    match e {
+        FieldElementExpression::Number(_) => {}
+        FieldElementExpression::Identifier(id) => v.visit_name(id),
+        FieldElementExpression::Add(box e1, box e2)
+        | FieldElementExpression::Sub(box e1, box e2)
+        | FieldElementExpression::Mult(box e1, box e2)
+        | FieldElementExpression::Div(box e1, box e2)
+        | FieldElementExpression::Pow(box e1, box e2) => {
+            v.visit_field_expression(e1);
+            v.visit_field_expression(e2);
+        }
+        FieldElementExpression::IfElse(box cond, box cons, box alt) => {
+            v.visit_boolean_expression(cond);
+            v.visit_field_expression(cons);
+            v.visit_field_expression(alt);
+        }
+        FieldElementExpression::FunctionCall(_, exps) => {
+            for e in exps {
+                v.visit_expression(e)
+            }
+        }
+        FieldElementExpression::Member(box s, _) => v.visit_struct_expression(s),
+        FieldElementExpression::Uint(box e) => v.visit_uint_expression(e),
+        FieldElementExpression::Select(box array, box index) => {
+            v.visit_array_expression(array);
+            v.visit_field_expression(index);
+        }
+    }
+}
+
+pub fn visit_boolean_expression<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    e: &BooleanExpression<'ast, T>,
+) {
+    match e {
+        BooleanExpression::Value(_) => {}
+        BooleanExpression::Identifier(id) => v.visit_name(id),
+        BooleanExpression::FieldEq(box e1, box e2) => {
+            v.visit_field_expression(e1);
+            v.visit_field_expression(e2);
+        }
+        BooleanExpression::BoolEq(box e1, box e2) => {
+            v.visit_boolean_expression(e1);
+            v.visit_boolean_expression(e2);
+        }
+        BooleanExpression::ArrayEq(box e1, box e2) => {
+            v.visit_array_expression(e1);
+            v.visit_array_expression(e2);
+        }
+        BooleanExpression::StructEq(box e1, box e2) => {
+            v.visit_struct_expression(e1);
+            v.visit_struct_expression(e2);
+        }
+        BooleanExpression::UintEq(box e1, box e2) => {
+            v.visit_uint_expression(e1);
+            v.visit_uint_expression(e2);
+        }
+        BooleanExpression::Lt(box e1, box e2)
+        | BooleanExpression::Le(box e1, box e2)
+        | BooleanExpression::Gt(box e1, box e2)
+        | BooleanExpression::Ge(box e1, box e2) => {
+            v.visit_field_expression(e1);
+            v.visit_field_expression(e2);
+        }
+        BooleanExpression::Or(box e1, box e2) | BooleanExpression::And(box e1, box e2) => {
+            v.visit_boolean_expression(e1);
+            v.visit_boolean_expression(e2);
+        }
+        BooleanExpression::Not(box e) => v.visit_boolean_expression(e),
+        BooleanExpression::FunctionCall(_, exps) => {
+            for e in exps {
+                v.visit_expression(e)
+            }
+        }
+        BooleanExpression::IfElse(box cond, box cons, box alt) => {
+            v.visit_boolean_expression(cond);
+            v.visit_boolean_expression(cons);
+            v.visit_boolean_expression(alt);
+        }
+        BooleanExpression::Member(box s, _) => v.visit_struct_expression(s),
+        BooleanExpression::Select(box array, box index) => {
+            v.visit_array_expression(array);
+            v.visit_field_expression(index);
+        }
+    }
+}
+
+pub fn visit_uint_expression<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    e: &UExpression<'ast, T>,
+) {
+    v.visit_uint_expression_inner(e.bitwidth, &e.inner)
+}
+
+pub fn visit_uint_expression_inner<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    _: UBitwidth,
+    e: &UExpressionInner<'ast, T>,
+) {
+    match e {
+        UExpressionInner::Value(_) => {}
+        UExpressionInner::Identifier(id) => v.visit_name(id),
+        UExpressionInner::Add(box left, box right)
+        | UExpressionInner::Sub(box left, box right)
+        | UExpressionInner::Mult(box left, box right)
+        | UExpressionInner::Div(box left, box right)
+        | UExpressionInner::Rem(box left, box right)
+        | UExpressionInner::Xor(box left, box right)
+        | UExpressionInner::And(box left, box right)
+        | UExpressionInner::Or(box left, box right) => {
+            v.visit_uint_expression(left);
+            v.visit_uint_expression(right);
+        }
+        UExpressionInner::LeftShift(box e, box by) | UExpressionInner::RightShift(box e, box by) => {
+            v.visit_uint_expression(e);
+            v.visit_field_expression(by);
+        }
+        UExpressionInner::Not(box e) => v.visit_uint_expression(e),
+        UExpressionInner::FunctionCall(_, exps) => {
+            for e in exps {
+                v.visit_expression(e)
+            }
+        }
+        UExpressionInner::Select(box array, box index) => {
+            v.visit_array_expression(array);
+            v.visit_field_expression(index);
+        }
+        UExpressionInner::IfElse(box cond, box cons, box alt) => {
+            v.visit_boolean_expression(cond);
+            v.visit_uint_expression(cons);
+            v.visit_uint_expression(alt);
+        }
+        UExpressionInner::Member(box s, _) => v.visit_struct_expression(s),
+    }
+}
+
+pub fn visit_array_expression<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    e: &ArrayExpression<'ast, T>,
+) {
+    v.visit_array_expression_inner(&e.ty, e.size, &e.inner)
+}
+
+pub fn visit_struct_expression<'ast, T: Field, V: Visitor<'ast, T> + ?Sized>(
+    v: &mut V,
+    e: &StructExpression<'ast, T>,
+) {
+    v.visit_struct_expression_inner(&e.ty, &e.inner)
+}